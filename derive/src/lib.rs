@@ -0,0 +1,273 @@
+//! A derive macro for the error kind enums of rust-error-management.
+//!
+//! `#[derive(ErrorKind)]` on an enum generates an implementation of
+//! `std::fmt::Display` and of `rust_error_management::ErrorKind`. Each
+//! variant must have an `#[error(...)]` attribute, which takes a format
+//! string and optional arguments, as for `write!`. The fields of the variant
+//! are in scope in the format string and arguments, by name for named fields
+//! and as `_0`, `_1`, etc., for unnamed fields:
+//!
+//! ```
+//! use rust_error_management::ErrorKind;
+//!
+//! #[derive(Debug, ErrorKind)]
+//! #[code(prefix = "DM")]
+//! enum Kind {
+//!     #[error("invalid argument: {description}")]
+//!     #[code(2)]
+//!     #[backtrace(disabled)]
+//!     InvalidArgument { description: String },
+//!     #[error("failed to stat metadata for device at {}", path.display())]
+//!     #[code(5)]
+//!     MetadataIoError { path: std::path::PathBuf },
+//!     #[error("ioctl failed with errno {_0}")]
+//!     #[code(6)]
+//!     Errno(i32),
+//! }
+//!
+//! let kind = Kind::InvalidArgument {
+//!     description: "32".into(),
+//! };
+//! assert_eq!(kind.to_string(), "invalid argument: 32");
+//! assert_eq!(kind.name(), "InvalidArgument");
+//! assert_eq!(kind.code().to_string(), "DM-0002");
+//! assert_eq!(Kind::Errno(16).to_string(), "ioctl failed with errno 16");
+//! ```
+//!
+//! Each variant must also have a `#[code(N)]` attribute, which assigns it a
+//! stable numeric code, unique within the enum. The enum may have a
+//! `#[code(prefix = "DM")]` attribute, which is prepended to the formatted
+//! code, e.g., "DM-0003".
+//!
+//! A variant may also have a `#[backtrace(enabled)]` or
+//! `#[backtrace(disabled)]` attribute, which declares its backtrace capture
+//! policy.
+
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, LitInt, LitStr, Token, Variant,
+};
+
+/// The information about a single variant obtained from its attributes
+struct VariantInfo<'a> {
+    variant: &'a Variant,
+    format: LitStr,
+    args: Vec<Expr>,
+    code: u32,
+    backtrace: Option<Ident>,
+}
+
+fn parse_variant(variant: &Variant) -> syn::Result<VariantInfo<'_>> {
+    let mut format = None;
+    let mut args = vec![];
+    let mut code = None;
+    let mut backtrace = None;
+    for attr in &variant.attrs {
+        if attr.path().is_ident("error") {
+            let mut exprs = attr
+                .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)?
+                .into_iter();
+            match exprs.next() {
+                Some(Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                })) => format = Some(lit),
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "expected a format string as the first argument",
+                    ))
+                }
+            }
+            args = exprs.collect();
+        } else if attr.path().is_ident("code") {
+            code = Some(attr.parse_args::<LitInt>()?.base10_parse::<u32>()?);
+        } else if attr.path().is_ident("backtrace") {
+            let policy: Ident = attr.parse_args()?;
+            if policy != "enabled" && policy != "disabled" {
+                return Err(syn::Error::new_spanned(
+                    policy,
+                    "expected `enabled` or `disabled`",
+                ));
+            }
+            backtrace = Some(policy);
+        }
+    }
+    match (format, code) {
+        (Some(format), Some(code)) => Ok(VariantInfo {
+            variant,
+            format,
+            args,
+            code,
+            backtrace,
+        }),
+        (None, _) => Err(syn::Error::new_spanned(
+            variant,
+            "missing #[error(...)] attribute",
+        )),
+        (_, None) => Err(syn::Error::new_spanned(
+            variant,
+            "missing #[code(...)] attribute",
+        )),
+    }
+}
+
+/// Return the code prefix given by the enum's #[code(prefix = "...")]
+/// attribute, or the empty string if there is none.
+fn parse_prefix(input: &DeriveInput) -> syn::Result<String> {
+    let mut prefix = String::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("code") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("prefix") {
+                    prefix = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `prefix`"))
+                }
+            })?;
+        }
+    }
+    Ok(prefix)
+}
+
+/// Return a pattern which matches the variant, binding each of its fields.
+fn pattern(variant: &Variant) -> TokenStream {
+    let name = &variant.ident;
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| &f.ident);
+            quote! { Self::#name { #(#names),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let names = (0..fields.unnamed.len())
+                .map(|i| Ident::new(&format!("_{}", i), Span::call_site()));
+            quote! { Self::#name( #(#names),* ) }
+        }
+        Fields::Unit => quote! { Self::#name },
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "ErrorKind can only be derived for enums",
+            ))
+        }
+    };
+    let infos = data
+        .variants
+        .iter()
+        .map(parse_variant)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let prefix = parse_prefix(input)?;
+
+    // The code must identify the variant, so no two variants may share one.
+    for (index, info) in infos.iter().enumerate() {
+        if let Some(other) = infos[..index].iter().find(|o| o.code == info.code) {
+            return Err(syn::Error::new_spanned(
+                info.variant,
+                format!(
+                    "code {} is already used by variant {}",
+                    info.code, other.variant.ident
+                ),
+            ));
+        }
+    }
+
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let display_arms = infos.iter().map(|info| {
+        let pattern = pattern(info.variant);
+        let format = &info.format;
+        let args = &info.args;
+        quote! {
+            #[allow(unused_variables)]
+            #pattern => ::std::write!(__formatter, #format #(, #args)*)
+        }
+    });
+
+    let name_arms = infos.iter().map(|info| {
+        let name = &info.variant.ident;
+        let text = name.to_string();
+        quote! { Self::#name { .. } => #text }
+    });
+
+    let code_arms = infos.iter().map(|info| {
+        let name = &info.variant.ident;
+        let code = info.code;
+        quote! { Self::#name { .. } => ::rust_error_management::ErrorCode::new(#prefix, #code) }
+    });
+
+    let backtrace_arms = infos.iter().map(|info| {
+        let name = &info.variant.ident;
+        let policy = match &info.backtrace {
+            Some(policy) if policy == "enabled" => {
+                quote! { ::std::option::Option::Some(::rust_error_management::BacktraceCapture::Enabled) }
+            }
+            Some(_) => {
+                quote! { ::std::option::Option::Some(::rust_error_management::BacktraceCapture::Disabled) }
+            }
+            None => quote! { ::std::option::Option::None },
+        };
+        quote! { Self::#name { .. } => #policy }
+    });
+
+    // A match on a reference to an empty enum is not exhaustive without any
+    // arms, but a match on the enum itself is.
+    let matching = |arms: Vec<TokenStream>| {
+        if arms.is_empty() {
+            quote! { match *self {} }
+        } else {
+            quote! { match self { #(#arms,)* } }
+        }
+    };
+    let display = matching(display_arms.collect());
+    let name = matching(name_arms.collect());
+    let code = matching(code_arms.collect());
+    let backtrace = matching(backtrace_arms.collect());
+
+    // The formatter is named so as not to be shadowed by a field of a
+    // variant, which is bound by its own name.
+    Ok(quote! {
+        impl #impl_generics ::std::fmt::Display for #ty #ty_generics #where_clause {
+            fn fmt(&self, __formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                #display
+            }
+        }
+
+        impl #impl_generics ::rust_error_management::ErrorKind for #ty #ty_generics #where_clause {
+            fn name(&self) -> &'static str {
+                #name
+            }
+
+            fn code(&self) -> ::rust_error_management::ErrorCode {
+                #code
+            }
+
+            fn backtrace_capture(
+                &self,
+            ) -> ::std::option::Option<::rust_error_management::BacktraceCapture> {
+                #backtrace
+            }
+        }
+    })
+}
+
+/// Derive Display and rust_error_management::ErrorKind for an enum of
+/// error kinds. See the crate documentation for the attributes.
+#[proc_macro_derive(ErrorKind, attributes(error, code, backtrace))]
+pub fn derive_error_kind(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,93 @@
+use rust_error_management::{BacktraceCapture, ErrorKind};
+
+#[derive(Debug, ErrorKind)]
+#[code(prefix = "XY")]
+enum Kind {
+    #[error("unit")]
+    #[code(1)]
+    Unit,
+    #[error("named {description} and {}", number + 1)]
+    #[code(2)]
+    #[backtrace(enabled)]
+    Named { description: String, number: u32 },
+    #[error("unnamed {_0} and {_1}")]
+    #[code(30)]
+    #[backtrace(disabled)]
+    Unnamed(&'static str, i32),
+    // A field may have the name the generated Display uses for its
+    // formatter in the standard library.
+    #[error("bad {f}")]
+    #[code(4)]
+    Shadowing { f: u32 },
+}
+
+#[derive(Debug, ErrorKind)]
+enum Unprefixed {
+    #[error("unprefixed")]
+    #[code(7)]
+    Only,
+}
+
+#[derive(Debug, ErrorKind)]
+enum Empty {}
+
+#[test]
+fn displays_with_fields_in_scope() {
+    assert_eq!(Kind::Unit.to_string(), "unit");
+    assert_eq!(
+        Kind::Named {
+            description: "thing".into(),
+            number: 1
+        }
+        .to_string(),
+        "named thing and 2"
+    );
+    assert_eq!(Kind::Unnamed("a", 2).to_string(), "unnamed a and 2");
+    assert_eq!(Kind::Shadowing { f: 3 }.to_string(), "bad 3");
+}
+
+#[test]
+fn names_are_variant_names() {
+    assert_eq!(Kind::Unit.name(), "Unit");
+    assert_eq!(Kind::Unnamed("a", 2).name(), "Unnamed");
+    assert_eq!(Kind::Shadowing { f: 3 }.name(), "Shadowing");
+}
+
+#[test]
+fn codes_have_prefix() {
+    assert_eq!(Kind::Unit.code().to_string(), "XY-0001");
+    assert_eq!(Kind::Unnamed("a", 2).code().number(), 30);
+    assert_eq!(Kind::Unnamed("a", 2).code().prefix(), "XY");
+    assert_eq!(Unprefixed::Only.code().to_string(), "0007");
+    assert_eq!(Unprefixed::Only.code().prefix(), "");
+}
+
+#[test]
+fn backtrace_policies_are_declared() {
+    assert_eq!(Kind::Unit.backtrace_capture(), None);
+    assert_eq!(
+        Kind::Named {
+            description: "".into(),
+            number: 0
+        }
+        .backtrace_capture(),
+        Some(BacktraceCapture::Enabled)
+    );
+    assert_eq!(
+        Kind::Unnamed("", 0).backtrace_capture(),
+        Some(BacktraceCapture::Disabled)
+    );
+}
+
+#[test]
+fn empty_enum_is_a_kind() {
+    fn is_kind<K: ErrorKind>() {}
+    is_kind::<Empty>();
+}
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
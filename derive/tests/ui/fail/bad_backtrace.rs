@@ -0,0 +1,11 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind {
+    #[error("first")]
+    #[code(1)]
+    #[backtrace(sometimes)]
+    First,
+}
+
+fn main() {}
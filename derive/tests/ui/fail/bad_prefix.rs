@@ -0,0 +1,11 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+#[code(suffix = "DM")]
+enum Kind {
+    #[error("first")]
+    #[code(1)]
+    First,
+}
+
+fn main() {}
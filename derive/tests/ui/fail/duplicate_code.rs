@@ -0,0 +1,13 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind {
+    #[error("first")]
+    #[code(1)]
+    First,
+    #[error("second")]
+    #[code(1)]
+    Second,
+}
+
+fn main() {}
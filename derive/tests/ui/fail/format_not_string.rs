@@ -0,0 +1,10 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind {
+    #[error(first)]
+    #[code(1)]
+    First,
+}
+
+fn main() {}
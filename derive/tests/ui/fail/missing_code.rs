@@ -0,0 +1,9 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind {
+    #[error("first")]
+    First,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind {
+    #[code(1)]
+    First,
+}
+
+fn main() {}
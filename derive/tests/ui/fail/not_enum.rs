@@ -0,0 +1,8 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+struct Kind {
+    field: u32,
+}
+
+fn main() {}
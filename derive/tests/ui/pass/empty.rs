@@ -0,0 +1,7 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+#[code(prefix = "XY")]
+enum Empty {}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use rust_error_management::ErrorKind;
+
+#[derive(Debug, ErrorKind)]
+enum Kind<T: std::fmt::Debug + std::fmt::Display> {
+    #[error("value {value}")]
+    #[code(1)]
+    Value { value: T },
+}
+
+fn main() {
+    assert_eq!(Kind::Value { value: 3 }.to_string(), "value 3");
+    assert_eq!(Kind::Value { value: "x" }.code().to_string(), "0001");
+}
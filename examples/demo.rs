@@ -0,0 +1,218 @@
+use std::error::Error;
+
+use rust_error_management::{
+    set_backtrace_capture, BacktraceCapture, Backtraces, DeviceInfo, ErrorKind, FrameFilter,
+    IoctlCommand, IoctlRequest, OurError, OurErrorKind, ResultExt,
+};
+
+// The errno for a busy device
+const EBUSY: i32 = 16;
+
+fn b() -> Result<(), OurError> {
+    let err = std::io::Error::other("oh no!");
+    let mut ours = OurError::new(OurErrorKind::ContextInitError);
+    ours.set_constituent(Box::new(err));
+    Err(ours)
+}
+
+fn c() -> Result<(), OurError> {
+    b()
+}
+
+fn d() -> Result<(), OurError> {
+    c().extend_with(|| OurErrorKind::InvalidArgument {
+        description: "32".into(),
+    })
+    .followed_by(|| OurErrorKind::IoctlResultTooLarge {
+        request: IoctlRequest::from(IoctlCommand::ListDevices),
+        requested: 1 << 32,
+        maximum: u32::MAX.into(),
+    })
+}
+
+// An ioctl failure, explained by an errno, which happened while cleaning up
+// after an earlier failure.
+fn e() -> Result<(), OurError> {
+    let ours = OurError::ioctl(
+        DeviceInfo::new("dm-0", 253, 0),
+        IoctlRequest::from(IoctlCommand::TableLoad),
+        EBUSY,
+    );
+    Err(c().expect_err("").set_subsequent(ours))
+}
+
+// Tearing down several devices, each of which fails independently; the
+// failures all precede the error reporting that the teardown failed.
+fn f() -> Result<(), OurError> {
+    let mut ours = OurError::new(OurErrorKind::InvalidArgument {
+        description: "teardown".into(),
+    });
+    for (name, minor) in &[("dm-0", 0), ("dm-1", 1)] {
+        ours.add_previous(Box::new(OurError::ioctl(
+            DeviceInfo::new(name, 253, *minor),
+            IoctlRequest::from(IoctlCommand::DevRemove),
+            EBUSY,
+        )));
+    }
+    ours.add_constituent(Box::new(c().expect_err("")));
+    Err(ours)
+}
+
+// A failure to stat a device node, which is explained by the io error.
+fn g() -> Result<std::fs::Metadata, OurError> {
+    let path = "/dev/mapper/no-such-device";
+    std::fs::metadata(path).map_err(|err| OurError::metadata_io(path, err))
+}
+
+// Reading a device's attribute, where an io error is converted by ?.
+fn h() -> Result<String, OurError> {
+    let text = std::fs::read_to_string("/sys/block/no-such-device/dm/name")?;
+    if text.is_empty() {
+        return Err("empty device name".into());
+    }
+    Ok(text)
+}
+
+fn main() {
+    // Capture backtraces regardless of the environment, so that they can be
+    // displayed.
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+
+    let err = d().expect_err("");
+
+    // We can downcast the source to OurError, since we know it is.
+    assert_eq!(
+        err.source()
+            .expect("")
+            .downcast_ref::<OurError>()
+            .expect("")
+            .kind(),
+        &OurErrorKind::InvalidArgument {
+            description: "32".into()
+        }
+    );
+
+    // The kind has a stable name, which is independent of its message.
+    assert_eq!(err.kind().name(), "IoctlResultTooLarge");
+    assert_eq!(err.code().to_string(), "DM-0004");
+
+    // Downcasting to std::io::Error will result in None
+    assert!(err
+        .source()
+        .expect("")
+        .downcast_ref::<std::io::Error>()
+        .is_none());
+
+    // The source is a previous error
+    assert_eq!(
+        err.previous()
+            .expect("")
+            .downcast_ref::<OurError>()
+            .expect("")
+            .kind(),
+        err.source()
+            .expect("")
+            .downcast_ref::<OurError>()
+            .expect("")
+            .kind()
+    );
+
+    // Since the source is a previous error, it can not be a constituent
+    // error.
+    assert!(err.constituent().is_none());
+
+    // An error may have both a constituent and a previous error.
+    let both = e().expect_err("");
+
+    // The kind identifies the device that failed.
+    println!("{}", both.kind());
+
+    // The io error that explains a metadata error is kept, together with its
+    // kind and OS error.
+    let metadata = g().expect_err("");
+    println!(
+        "{}: {:?}, OS error {:?}",
+        metadata,
+        metadata.io_error_kind().expect(""),
+        metadata.raw_os_error().expect("")
+    );
+
+    // ? converts an io error into an OurError, which keeps it.
+    let converted = h().expect_err("");
+    println!("{}", converted.report());
+
+    // An OurError converts into an io error of a matching kind, from which
+    // it can be recovered.
+    let io = std::io::Error::from(metadata);
+    println!("As an io error: {:?}", io.kind());
+
+    // An error can be returned from a spawned thread.
+    let joined = std::thread::spawn(d).join().expect("").expect_err("");
+    println!("Returned from a thread: {}", joined);
+
+    // An error may have several constituents and previous errors.
+    let many = f().expect_err("");
+
+    // The whole tree can be searched for an error of some type or kind.
+    println!(
+        "The io error behind it all: {}",
+        err.find::<std::io::Error>().expect("")
+    );
+    println!(
+        "The first failed ioctl: {}",
+        many.find_kind(|k| matches!(k, OurErrorKind::IoctlError { .. }))
+            .expect("")
+    );
+
+    // A suberror can be detached, e.g., to re-raise the OS error that
+    // explains an ioctl failure.
+    let mut ioctl = OurError::ioctl(
+        DeviceInfo::new("dm-0", 253, 0),
+        IoctlRequest::from(IoctlCommand::DevRemove),
+        EBUSY,
+    );
+    let os = ioctl.take_constituent_as::<std::io::Error>().expect("");
+    println!("Detached from the ioctl error: {}", os);
+
+    // An error can be taken apart entirely, and its suberrors moved into
+    // another error.
+    let (_, suberrors, _) = f().expect_err("").into_parts();
+    let mut other = OurError::from("teardown failed");
+    for suberror in suberrors {
+        other.add_suberror(suberror);
+    }
+    print!(
+        "The same suberrors, under another error: {}",
+        other.report()
+    );
+    println!();
+
+    println!("The error's debug representation: {:?}", err);
+    println!();
+    println!("Just the error: {}", err);
+    println!();
+    print!("The error's report: {}", err.report());
+    println!();
+    print!("A report on several errors: {}", many.report());
+    println!();
+    print!(
+        "The error's report, with backtrace: {}",
+        err.report().backtraces(Backtraces::Head)
+    );
+    println!();
+    print!(
+        "The error's report, with all backtraces: {}",
+        err.report().backtraces(Backtraces::All)
+    );
+    println!();
+    print!(
+        "Just this error's backtrace: {:?}",
+        err.our_backtrace().expect("")
+    );
+    println!();
+    print!(
+        "Just this error's backtrace, only frames in this example: {:?}",
+        err.filtered_backtrace(&FrameFilter::new().only_crates(vec!["demo"]))
+            .expect("")
+    );
+}
@@ -0,0 +1,41 @@
+use rust_error_management::{
+    set_backtrace_capture, BacktraceCapture, IoctlCommand, IoctlRequest, OurError, OurErrorKind,
+    RemoteError, Report,
+};
+
+fn b() -> Result<(), OurError> {
+    let err = std::io::Error::other("oh no!");
+    let mut ours = OurError::new(OurErrorKind::ContextInitError);
+    ours.set_constituent(Box::new(err));
+    Err(ours)
+}
+
+fn d() -> Result<(), OurError> {
+    Err(b()
+        .expect_err("")
+        .set_extension(OurError::new(OurErrorKind::InvalidArgument {
+            description: "32".into(),
+        }))
+        .set_subsequent(OurError::new(OurErrorKind::IoctlResultTooLarge {
+            request: IoctlRequest::from(IoctlCommand::ListDevices),
+            requested: 1 << 32,
+            maximum: u32::MAX.into(),
+        })))
+}
+
+fn main() {
+    // Capture backtraces regardless of the environment, so that they can be
+    // displayed.
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+
+    let err = d().expect_err("");
+
+    let json = serde_json::to_string_pretty(&err).expect("");
+    println!("The serialized error: {}", json);
+    println!();
+
+    // The serialized error can be reconstructed as a RemoteError, e.g., in
+    // another process, and reported as the original would be.
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    print!("The deserialized error's report: {}", Report::new(&remote));
+}
@@ -0,0 +1,121 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use backtrace::Backtrace;
+
+use crate::kind::ErrorKind;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Whether a backtrace is captured when an error is created
+pub enum BacktraceCapture {
+    /// Capture a backtrace
+    Enabled,
+    /// Do not capture a backtrace
+    Disabled,
+}
+
+impl BacktraceCapture {
+    fn to_u8(capture: Option<BacktraceCapture>) -> u8 {
+        match capture {
+            None => 0,
+            Some(BacktraceCapture::Enabled) => 1,
+            Some(BacktraceCapture::Disabled) => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<BacktraceCapture> {
+        match value {
+            1 => Some(BacktraceCapture::Enabled),
+            2 => Some(BacktraceCapture::Disabled),
+            _ => None,
+        }
+    }
+}
+
+// The programmatic override, encoded by BacktraceCapture::to_u8
+static OVERRIDE: AtomicU8 = AtomicU8::new(0);
+
+// The policy set by the environment, which is read only once
+static FROM_ENV: OnceLock<Option<BacktraceCapture>> = OnceLock::new();
+
+/// Return the policy set by the environment, if any.
+/// As for std, RUST_LIB_BACKTRACE takes precedence over RUST_BACKTRACE, and
+/// any value but "0" enables capture.
+fn from_env() -> Option<BacktraceCapture> {
+    *FROM_ENV.get_or_init(|| {
+        std::env::var_os("RUST_LIB_BACKTRACE")
+            .or_else(|| std::env::var_os("RUST_BACKTRACE"))
+            .map(|value| {
+                if value == "0" {
+                    BacktraceCapture::Disabled
+                } else {
+                    BacktraceCapture::Enabled
+                }
+            })
+    })
+}
+
+/// Override, or with None, cease to override, the policy set by the
+/// environment for capturing backtraces when errors are created.
+pub fn set_backtrace_capture(capture: Option<BacktraceCapture>) {
+    OVERRIDE.store(BacktraceCapture::to_u8(capture), Ordering::Relaxed);
+}
+
+/// Return the global policy for capturing backtraces when errors are
+/// created, if any. This is the programmatic override, if set; otherwise, the
+/// policy set by the RUST_LIB_BACKTRACE or RUST_BACKTRACE environment
+/// variable. The global policy applies to errors of any kind. If it is None,
+/// each kind declares its own policy, and a kind which declares none has no
+/// backtrace, as for std.
+pub fn backtrace_capture() -> Option<BacktraceCapture> {
+    BacktraceCapture::from_u8(OVERRIDE.load(Ordering::Relaxed)).or_else(from_env)
+}
+
+/// Return the policy for capturing a backtrace for an error of the given
+/// kind. The global policy, if set, overrides the kind's own policy.
+pub(crate) fn backtrace_capture_for(kind: &impl ErrorKind) -> BacktraceCapture {
+    backtrace_capture()
+        .or_else(|| kind.backtrace_capture())
+        .unwrap_or(BacktraceCapture::Disabled)
+}
+
+/// A backtrace which is captured without resolving its symbols, since
+/// resolution is expensive and most errors are handled without their
+/// backtraces ever being examined. The symbols are resolved on first access.
+pub(crate) struct LazyBacktrace {
+    // The raw frames, as captured
+    unresolved: Backtrace,
+
+    // A copy of the frames with their symbols resolved, once required
+    resolved: OnceLock<Backtrace>,
+}
+
+impl LazyBacktrace {
+    /// Capture the frames of the current backtrace, without resolving them.
+    pub(crate) fn capture() -> LazyBacktrace {
+        LazyBacktrace {
+            unresolved: Backtrace::new_unresolved(),
+            resolved: OnceLock::new(),
+        }
+    }
+
+    /// Obtain the backtrace, resolving its symbols if not yet resolved.
+    pub(crate) fn resolved(&self) -> &Backtrace {
+        self.resolved.get_or_init(|| {
+            let mut backtrace = self.unresolved.clone();
+            backtrace.resolve();
+            backtrace
+        })
+    }
+
+    /// Obtain the backtrace, resolved only if it has already been resolved.
+    pub(crate) fn into_backtrace(self) -> Backtrace {
+        self.resolved.into_inner().unwrap_or(self.unresolved)
+    }
+}
+
+impl std::fmt::Debug for LazyBacktrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.resolved().fmt(f)
+    }
+}
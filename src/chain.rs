@@ -0,0 +1,224 @@
+use std::error::Error;
+
+use crate::error::OurError;
+use crate::kind::OurErrorKind;
+#[cfg(feature = "serde")]
+use crate::remote::RemoteError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+/// How an error in a chain relates to the error which precedes it in the
+/// chain, i.e., its parent.
+pub enum Relation {
+    /// The error occurred before its parent
+    Previous,
+    /// The error is further explained or extended by its parent
+    Constituent,
+}
+
+impl std::fmt::Display for Relation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Relation::Previous => write!(f, "preceded by"),
+            Relation::Constituent => write!(f, "caused by"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A single error in a chain or tree, together with its relation to its
+/// parent and its depth.
+pub struct Link<'a> {
+    /// The distance from the error at the head of the chain or the root of
+    /// the tree, which has depth 0.
+    pub depth: usize,
+    /// The relation to the parent; None for the error at the head of the
+    /// chain.
+    pub relation: Option<Relation>,
+    /// The error itself
+    pub error: &'a (dyn Error + 'static),
+}
+
+/// Return the suberrors of an error along with their relation to that error.
+/// An error which does not record the relation of its suberrors has at most
+/// one, its source. Its relation is unknown, but the documented meaning of
+/// source() is that it is the lower-level source of the error, so it is
+/// treated as a constituent.
+pub(crate) fn suberrors_of<'a>(
+    error: &'a (dyn Error + 'static),
+) -> Vec<(Relation, &'a (dyn Error + 'static))> {
+    if let Some(ours) = error.downcast_ref::<OurError>() {
+        return ours.suberrors().collect();
+    }
+    #[cfg(feature = "serde")]
+    {
+        if let Some(remote) = error.downcast_ref::<RemoteError>() {
+            return remote.suberrors().collect();
+        }
+    }
+    error
+        .source()
+        .map(|s| (Relation::Constituent, s))
+        .into_iter()
+        .collect()
+}
+
+/// Return the kind of an error, if it is an OurError, or a RemoteError whose
+/// kind was recognized.
+pub(crate) fn kind_of<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a OurErrorKind> {
+    if let Some(ours) = error.downcast_ref::<OurError>() {
+        return Some(ours.kind());
+    }
+    #[cfg(feature = "serde")]
+    {
+        if let Some(remote) = error.downcast_ref::<RemoteError>() {
+            return remote.kind();
+        }
+    }
+    None
+}
+
+/// Return the first error of type T among error and the errors it is built
+/// from, in the order of a tree rooted at error, following only the links of
+/// the given relation, if any.
+pub(crate) fn find_in<'a, T: Error + 'static>(
+    error: &'a (dyn Error + 'static),
+    relation: Option<Relation>,
+) -> Option<&'a T> {
+    let mut tree = match relation {
+        Some(relation) => Tree::along(error, relation),
+        None => Tree::new(error),
+    };
+    tree.find_map(|l| l.error.downcast_ref::<T>())
+}
+
+/// Return the first kind satisfying predicate among the kinds of error and
+/// the errors it is built from, in the order of a tree rooted at error.
+pub(crate) fn find_kind_in<'a, P>(
+    error: &'a (dyn Error + 'static),
+    mut predicate: P,
+) -> Option<&'a OurErrorKind>
+where
+    P: FnMut(&OurErrorKind) -> bool,
+{
+    Tree::new(error)
+        .filter_map(|l| kind_of(l.error))
+        .find(|k| predicate(k))
+}
+
+/// Return the error reached from error by repeatedly following its first
+/// suberror of the given relation, until there is none.
+pub(crate) fn last_along<'a>(
+    error: &'a (dyn Error + 'static),
+    relation: Relation,
+) -> &'a (dyn Error + 'static) {
+    let mut current = error;
+    while let Some((_, next)) = suberrors_of(current)
+        .into_iter()
+        .find(|(r, _)| *r == relation)
+    {
+        current = next;
+    }
+    current
+}
+
+/// Return the source of an error along with its relation to that error.
+fn source_of<'a>(
+    error: &'a (dyn Error + 'static),
+) -> Option<(Relation, &'a (dyn Error + 'static))> {
+    suberrors_of(error).into_iter().next()
+}
+
+#[derive(Clone, Debug)]
+/// An iterator over an error and its successive sources, which yields each
+/// error together with its relation to its parent.
+pub struct Chain<'a> {
+    next: Option<Link<'a>>,
+}
+
+impl<'a> Chain<'a> {
+    /// Create a chain beginning with error.
+    pub fn new(error: &'a (dyn Error + 'static)) -> Chain<'a> {
+        Chain {
+            next: Some(Link {
+                depth: 0,
+                relation: None,
+                error,
+            }),
+        }
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = Link<'a>;
+
+    fn next(&mut self) -> Option<Link<'a>> {
+        let current = self.next.take()?;
+        self.next = source_of(current.error).map(|(relation, error)| Link {
+            depth: current.depth + 1,
+            relation: Some(relation),
+            error,
+        });
+        Some(current)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A depth-first, pre-order iterator over an error and all its suberrors,
+/// which yields each error together with its relation to its parent and its
+/// depth. The constituents of an error are visited before its previous
+/// errors.
+pub struct Tree<'a> {
+    stack: Vec<Link<'a>>,
+    // The only relation to follow, if not all
+    along: Option<Relation>,
+}
+
+impl<'a> Tree<'a> {
+    /// Create a tree rooted at error.
+    pub fn new(error: &'a (dyn Error + 'static)) -> Tree<'a> {
+        Tree {
+            stack: vec![Link {
+                depth: 0,
+                relation: None,
+                error,
+            }],
+            along: None,
+        }
+    }
+
+    /// Create a tree rooted at error which follows only the links of the
+    /// given relation, e.g., only constituents, which explain an error,
+    /// and not previous errors, which merely preceded it.
+    pub fn along(error: &'a (dyn Error + 'static), relation: Relation) -> Tree<'a> {
+        Tree {
+            along: Some(relation),
+            ..Tree::new(error)
+        }
+    }
+}
+
+impl<'a> Iterator for Tree<'a> {
+    type Item = Link<'a>;
+
+    fn next(&mut self) -> Option<Link<'a>> {
+        let current = self.stack.pop()?;
+        let along = self.along;
+        self.stack.extend(
+            suberrors_of(current.error)
+                .into_iter()
+                .filter(|(relation, _)| along.is_none_or(|r| r == *relation))
+                .rev()
+                .map(|(relation, error)| Link {
+                    depth: current.depth + 1,
+                    relation: Some(relation),
+                    error,
+                }),
+        );
+        Some(current)
+    }
+}
@@ -0,0 +1,43 @@
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Identifies the devicemapper device involved in an error, as reported by
+/// the kernel in the dm_ioctl structure.
+pub struct DeviceInfo {
+    /// The name of the device
+    pub name: String,
+    /// The uuid of the device, if it has one
+    pub uuid: Option<String>,
+    /// The major number of the device
+    pub major: u32,
+    /// The minor number of the device
+    pub minor: u32,
+    /// The event number of the device
+    pub event_nr: u32,
+    /// The DM_*_FLAG flags of the device
+    pub flags: u32,
+}
+
+impl DeviceInfo {
+    /// Create the information for a device with the given name and device
+    /// number, with no uuid, event number 0, and no flags set.
+    pub fn new(name: &str, major: u32, minor: u32) -> DeviceInfo {
+        DeviceInfo {
+            name: name.into(),
+            uuid: None,
+            major,
+            minor,
+            event_nr: 0,
+            flags: 0,
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} ({}:{})", self.name, self.major, self.minor)?;
+        if let Some(uuid) = &self.uuid {
+            write!(f, ", uuid {}", uuid)?;
+        }
+        write!(f, ", event {}, flags {:#x}", self.event_nr, self.flags)
+    }
+}
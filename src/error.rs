@@ -0,0 +1,535 @@
+use std::path::PathBuf;
+
+use backtrace::Backtrace;
+
+use crate::capture::{backtrace_capture_for, BacktraceCapture, LazyBacktrace};
+use crate::chain::{find_in, find_kind_in, last_along, Chain, Relation, Tree};
+use crate::device::DeviceInfo;
+use crate::filter::FrameFilter;
+use crate::ioctl::IoctlRequest;
+use crate::kind::{ErrorCode, ErrorKind, OurErrorKind};
+use crate::report::Report;
+
+#[derive(Debug)]
+/// What relation the component error has to its parent.
+/// The error must be Send and Sync, so that its parent may be.
+pub enum Suberror {
+    /// The error occurred before the parent error
+    Previous(Box<dyn std::error::Error + Send + Sync>),
+    /// The error is further explained or extended by the parent
+    Constituent(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Suberror {
+    /// The relation the suberror has to its parent
+    pub fn relation(&self) -> Relation {
+        match self {
+            Suberror::Previous(_) => Relation::Previous,
+            Suberror::Constituent(_) => Relation::Constituent,
+        }
+    }
+
+    /// The suberror itself
+    pub fn error(&self) -> &(dyn std::error::Error + 'static) {
+        match self {
+            Suberror::Previous(c) => &**c,
+            Suberror::Constituent(c) => &**c,
+        }
+    }
+
+    /// Take the suberror itself, discarding its relation
+    pub fn into_error(self) -> Box<dyn std::error::Error + Send + Sync> {
+        match self {
+            Suberror::Previous(c) => c,
+            Suberror::Constituent(c) => c,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An error which records, in addition to its kind, the error from which it
+/// arose and the backtrace at the site where it was created.
+pub struct OurError {
+    // Errors for which this error is a further explanation, i.e.,
+    // constituent errors.
+    constituents: Vec<Box<dyn std::error::Error + Send + Sync>>,
+
+    // Errors that occurred previously, and which presumably caused the
+    // current code to be run and encounter its own, novel error.
+    // An error may have both constituent and previous errors, e.g., an
+    // ioctl failure explained by an errno, which happened while cleaning
+    // up after an earlier failure. A batch operation may yield several
+    // independent failures which all precede a single error.
+    previous: Vec<Box<dyn std::error::Error + Send + Sync>>,
+
+    // The backtrace at the site the error is returned, if captured,
+    // resolved only when it is first examined. It is boxed, since it is
+    // large, and errors are returned by value.
+    backtrace: Option<Box<LazyBacktrace>>,
+
+    // Distinguish among different errors with an ErrorKind
+    specifics: OurErrorKind,
+}
+
+// An OurError, and so each of its suberrors, must be Send and Sync, so that
+// it can be returned from a spawned thread or held across an await on a
+// multi-threaded runtime.
+const _: () = {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    let _ = assert_send_sync::<OurError>;
+    let _ = assert_send_sync::<Suberror>;
+};
+
+impl OurError {
+    /// Create a new error of the given kind, capturing a backtrace if the
+    /// global policy, or if that is not set, the kind's own policy allows.
+    /// Only the raw frames are captured; symbols are resolved when the
+    /// backtrace is first examined.
+    pub fn new(kind: OurErrorKind) -> OurError {
+        OurError {
+            backtrace: match backtrace_capture_for(&kind) {
+                BacktraceCapture::Enabled => Some(Box::new(LazyBacktrace::capture())),
+                BacktraceCapture::Disabled => None,
+            },
+            constituents: vec![],
+            previous: vec![],
+            specifics: kind,
+        }
+    }
+
+    /// Create an error for a devicemapper ioctl which failed with errno.
+    /// The corresponding OS error is attached as the constituent.
+    pub fn ioctl(device_info: DeviceInfo, request: IoctlRequest, errno: i32) -> OurError {
+        let mut err = OurError::new(OurErrorKind::IoctlError {
+            device_info: Box::new(device_info),
+            request,
+            errno,
+        });
+        err.add_constituent(Box::new(std::io::Error::from_raw_os_error(errno)));
+        err
+    }
+
+    /// Create an error for a failure to stat the metadata of the device at
+    /// path. The io error is attached as the constituent, and its kind and
+    /// raw OS error remain available through io_error_kind() and
+    /// raw_os_error().
+    pub fn metadata_io<P: Into<PathBuf>>(path: P, error: std::io::Error) -> OurError {
+        let mut err = OurError::new(OurErrorKind::MetadataIoError { path: path.into() });
+        err.add_constituent(Box::new(error));
+        err
+    }
+
+    /// Return the kind of this error.
+    pub fn kind(&self) -> &OurErrorKind {
+        &self.specifics
+    }
+
+    /// Return the stable code identifying the kind of this error.
+    pub fn code(&self) -> ErrorCode {
+        self.specifics.code()
+    }
+
+    /// Return the optional backtrace associated with this error, which is
+    /// None if no backtrace was captured. Its symbols are resolved on the
+    /// first call.
+    // Note that the function name is our_backtrace, so that it does not
+    // conflict with a future possible backtrace function in the Error trait.
+    pub fn our_backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_ref().map(|b| b.resolved())
+    }
+
+    /// Return the optional backtrace associated with this error, with
+    /// uninteresting frames removed by filter.
+    pub fn filtered_backtrace(&self, filter: &FrameFilter) -> Option<Backtrace> {
+        self.our_backtrace().map(|b| filter.apply(b))
+    }
+
+    /// Set extension as the extension on this error.
+    /// Return the head of the chain, now subsequent.
+    /// This error is added to the constituents of extension, after any it
+    /// already had, e.g., the OS error attached by ioctl(); none are lost.
+    pub fn set_extension(self, mut extension: OurError) -> OurError {
+        extension.add_constituent(Box::new(self));
+        extension
+    }
+
+    /// Set subsequent as the subsequent error for this error.
+    /// Return the head of the chain, now subsequent.
+    /// This error is added to the previous errors of subsequent, after any
+    /// it already had; none are lost.
+    pub fn set_subsequent(self, mut subsequent: OurError) -> OurError {
+        subsequent.add_previous(Box::new(self));
+        subsequent
+    }
+
+    /// Set constituent as the only constituent of this error, discarding
+    /// any it already had, including those attached by a constructor such
+    /// as ioctl() or a From conversion. Use add_constituent() to keep them.
+    /// The previous errors, if any, are unaffected.
+    pub fn set_constituent(&mut self, constituent: Box<dyn std::error::Error + Send + Sync>) {
+        self.constituents = vec![constituent];
+    }
+
+    /// Set previous as the only previous error, discarding any it already
+    /// had. Use add_previous() to keep them.
+    /// The constituent errors, if any, are unaffected.
+    pub fn set_previous(&mut self, previous: Box<dyn std::error::Error + Send + Sync>) {
+        self.previous = vec![previous];
+    }
+
+    /// Add constituent to the constituents of this error.
+    pub fn add_constituent(&mut self, constituent: Box<dyn std::error::Error + Send + Sync>) {
+        self.constituents.push(constituent);
+    }
+
+    /// Add previous to the previous errors of this error.
+    pub fn add_previous(&mut self, previous: Box<dyn std::error::Error + Send + Sync>) {
+        self.previous.push(previous);
+    }
+
+    /// Add suberror to the constituents or the previous errors of this
+    /// error, according to its relation.
+    pub fn add_suberror(&mut self, suberror: Suberror) {
+        match suberror {
+            Suberror::Previous(p) => self.add_previous(p),
+            Suberror::Constituent(c) => self.add_constituent(c),
+        }
+    }
+
+    /// Remove the first immediate constituent error, if there is one, and
+    /// return it, e.g., to re-raise it or to attach it to another error.
+    pub fn take_constituent(&mut self) -> Option<Box<dyn std::error::Error + Send + Sync>> {
+        take_first(&mut self.constituents, |_| true)
+    }
+
+    /// Remove the first immediate previous error, if there is one, and
+    /// return it.
+    pub fn take_previous(&mut self) -> Option<Box<dyn std::error::Error + Send + Sync>> {
+        take_first(&mut self.previous, |_| true)
+    }
+
+    /// Remove the first immediate constituent error of type T, if there is
+    /// one, and return it. The other constituents are unaffected.
+    pub fn take_constituent_as<T: std::error::Error + 'static>(&mut self) -> Option<Box<T>> {
+        take_first(&mut self.constituents, |c| c.is::<T>()).and_then(|c| c.downcast().ok())
+    }
+
+    /// Remove the first immediate previous error of type T, if there is
+    /// one, and return it. The other previous errors are unaffected.
+    pub fn take_previous_as<T: std::error::Error + 'static>(&mut self) -> Option<Box<T>> {
+        take_first(&mut self.previous, |p| p.is::<T>()).and_then(|p| p.downcast().ok())
+    }
+
+    /// Take this error apart, obtaining its kind, its immediate suberrors,
+    /// constituents first, as for suberrors(), and its backtrace, if one was
+    /// captured. The backtrace's symbols are resolved only if they already
+    /// were; use Backtrace::resolve() to resolve them.
+    pub fn into_parts(self) -> (OurErrorKind, Vec<Suberror>, Option<Backtrace>) {
+        let suberrors = self
+            .constituents
+            .into_iter()
+            .map(Suberror::Constituent)
+            .chain(self.previous.into_iter().map(Suberror::Previous))
+            .collect();
+        (
+            self.specifics,
+            suberrors,
+            self.backtrace.map(|b| b.into_backtrace()),
+        )
+    }
+
+    /// Obtain the first immediate previous error, if there is one
+    pub fn previous(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.previous
+            .first()
+            .map(|p| &**p as &(dyn std::error::Error + 'static))
+    }
+
+    /// Obtain the first immediate constituent error, if there is one
+    pub fn constituent(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.constituents
+            .first()
+            .map(|c| &**c as &(dyn std::error::Error + 'static))
+    }
+
+    /// Obtain all the immediate previous errors, in the order added
+    pub fn previous_errors(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        self.previous
+            .iter()
+            .map(|p| &**p as &(dyn std::error::Error + 'static))
+    }
+
+    /// Obtain all the immediate constituent errors, in the order added
+    pub fn constituents(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        self.constituents
+            .iter()
+            .map(|c| &**c as &(dyn std::error::Error + 'static))
+    }
+
+    /// Obtain the first immediate constituent error which is an io error,
+    /// if there is one, e.g., the OS error of an ioctl or a metadata error.
+    pub fn io_error(&self) -> Option<&std::io::Error> {
+        self.constituents()
+            .find_map(|c| c.downcast_ref::<std::io::Error>())
+    }
+
+    /// Return the kind of the io error returned by io_error(), if any.
+    pub fn io_error_kind(&self) -> Option<std::io::ErrorKind> {
+        self.io_error().map(|e| e.kind())
+    }
+
+    /// Return the raw OS error of the io error returned by io_error(), if
+    /// any, and if it was an OS error.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.io_error().and_then(|e| e.raw_os_error())
+    }
+
+    /// Obtain all the immediate suberrors of this error, together with
+    /// their relation to this error. Constituents precede previous errors.
+    pub fn suberrors(
+        &self,
+    ) -> impl Iterator<Item = (Relation, &(dyn std::error::Error + 'static))> {
+        self.constituents()
+            .map(|c| (Relation::Constituent, c))
+            .chain(self.previous_errors().map(|p| (Relation::Previous, p)))
+    }
+
+    /// Obtain the immediate source of this error along with its relation
+    /// to this error. The first constituent takes precedence over the first
+    /// previous error, as for source().
+    pub(crate) fn source_with_relation(
+        &self,
+    ) -> Option<(Relation, &(dyn std::error::Error + 'static))> {
+        self.suberrors().next()
+    }
+
+    /// Iterate over this error and its successive sources, obtaining the
+    /// relation of each error to its parent. Unlike repeated calls to
+    /// source(), this distinguishes between previous and constituent errors.
+    /// The sources of foreign errors, which have no such distinction, are
+    /// treated as constituents. Like source(), the chain follows only the
+    /// first constituent of an error which has both constituent and previous
+    /// errors; use tree() to visit all of them.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain::new(self)
+    }
+
+    /// Iterate depth-first over this error and all the errors it is built
+    /// from, i.e., its suberrors, their suberrors, and so on. Each error is
+    /// obtained together with its relation to its parent and its depth in
+    /// the tree.
+    pub fn tree(&self) -> Tree<'_> {
+        Tree::new(self)
+    }
+
+    /// Return the deepest explanation of this error, i.e., the error reached
+    /// by following only first constituents, and the sources of foreign
+    /// errors, for as long as there are any. Previous errors are not
+    /// followed, since they did not cause this error, but only preceded it.
+    /// An error without constituents is its own root cause.
+    ///
+    /// ```
+    /// use rust_error_management::{OurError, OurErrorKind, ResultExt};
+    ///
+    /// fn b() -> Result<(), OurError> {
+    ///     let mut ours = OurError::new(OurErrorKind::ContextInitError);
+    ///     ours.set_constituent(Box::new(std::io::Error::other("oh no!")));
+    ///     Err(ours)
+    /// }
+    ///
+    /// fn c() -> Result<(), OurError> {
+    ///     b()
+    /// }
+    ///
+    /// fn d() -> Result<(), OurError> {
+    ///     c().extend_with(|| OurErrorKind::InvalidArgument {
+    ///         description: "32".into(),
+    ///     })
+    /// }
+    ///
+    /// // The io error explains the failure to initialize the context, which
+    /// // explains the invalid argument.
+    /// let err = d().unwrap_err();
+    /// assert_eq!(err.root_cause().to_string(), "oh no!");
+    ///
+    /// // An error which occurred after the invalid argument is not explained
+    /// // by it.
+    /// let err = d().followed_by(|| OurErrorKind::IoError).unwrap_err();
+    /// assert_eq!(err.root_cause().to_string(), "I/O error");
+    /// ```
+    pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+        last_along(self, Relation::Constituent)
+    }
+
+    /// Return the earliest error in the sequence of errors which ends with
+    /// this one, i.e., the error reached by following only first previous
+    /// errors, for as long as there are any. An error without previous
+    /// errors is its own earliest error.
+    ///
+    /// ```
+    /// use rust_error_management::{OurError, OurErrorKind, ResultExt};
+    ///
+    /// fn b() -> Result<(), OurError> {
+    ///     let mut ours = OurError::new(OurErrorKind::ContextInitError);
+    ///     ours.set_constituent(Box::new(std::io::Error::other("oh no!")));
+    ///     Err(ours)
+    /// }
+    ///
+    /// fn c() -> Result<(), OurError> {
+    ///     b()
+    /// }
+    ///
+    /// fn d() -> Result<(), OurError> {
+    ///     c().extend_with(|| OurErrorKind::InvalidArgument {
+    ///         description: "32".into(),
+    ///     })
+    ///     .followed_by(|| OurErrorKind::Other {
+    ///         description: "cleanup failed".into(),
+    ///     })
+    /// }
+    ///
+    /// // The invalid argument occurred first, and is explained by the
+    /// // failure to initialize the context, and that by the io error.
+    /// let err = d().unwrap_err();
+    /// let earliest = err.earliest().downcast_ref::<OurError>().unwrap();
+    /// assert_eq!(earliest.to_string(), "invalid argument: 32");
+    /// assert_eq!(earliest.root_cause().to_string(), "oh no!");
+    ///
+    /// // The later error was not caused by any of them.
+    /// assert_eq!(err.root_cause().to_string(), "cleanup failed");
+    /// ```
+    pub fn earliest(&self) -> &(dyn std::error::Error + 'static) {
+        last_along(self, Relation::Previous)
+    }
+
+    /// Return the first error of type T among this error and all the errors
+    /// it is built from, in the order of tree(). Foreign errors are searched
+    /// through their sources.
+    pub fn find<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        find_in(self, None)
+    }
+
+    /// Return the first error of type T among this error and the errors
+    /// reached from it by following only the links of the given relation,
+    /// e.g., only Relation::Constituent, to find the errors that explain
+    /// this one.
+    pub fn find_along<T: std::error::Error + 'static>(&self, relation: Relation) -> Option<&T> {
+        find_in(self, Some(relation))
+    }
+
+    /// Return the first kind satisfying predicate among the kinds of this
+    /// error and all the errors it is built from, in the order of tree().
+    /// The kinds of deserialized remote errors are included.
+    pub fn find_kind<P>(&self, predicate: P) -> Option<&OurErrorKind>
+    where
+        P: FnMut(&OurErrorKind) -> bool,
+    {
+        find_kind_in(self, predicate)
+    }
+
+    /// Obtain a multi-line, human-readable report on this error and all the
+    /// errors it is built from.
+    pub fn report(&self) -> Report<'_> {
+        Report::new(self)
+    }
+}
+
+impl std::error::Error for OurError {
+    /// Return the source of this error. If this error has constituents, the
+    /// first is the source, since it is a lower-level error which this error
+    /// explains. Otherwise, the source is the first previous error, if there
+    /// is one. Use chain() or tree() to learn the relation of each source.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source_with_relation().map(|(_, e)| e)
+    }
+}
+
+// Conversions, so that ? can be applied to the results of io and system
+// calls, and to messages, in a function which returns an OurError. The
+// original error, if any, is kept as the constituent.
+impl From<std::io::Error> for OurError {
+    fn from(err: std::io::Error) -> OurError {
+        let mut ours = OurError::new(OurErrorKind::IoError);
+        ours.add_constituent(Box::new(err));
+        ours
+    }
+}
+
+#[cfg(feature = "nix")]
+impl From<nix::Error> for OurError {
+    fn from(err: nix::Error) -> OurError {
+        let mut ours = OurError::new(OurErrorKind::IoError);
+        ours.add_constituent(Box::new(err));
+        ours
+    }
+}
+
+impl From<String> for OurError {
+    fn from(description: String) -> OurError {
+        OurError::new(OurErrorKind::Other { description })
+    }
+}
+
+impl From<&str> for OurError {
+    fn from(description: &str) -> OurError {
+        OurError::from(description.to_string())
+    }
+}
+
+/// Remove the first error in errors which satisfies predicate, and return
+/// it.
+fn take_first<P>(
+    errors: &mut Vec<Box<dyn std::error::Error + Send + Sync>>,
+    predicate: P,
+) -> Option<Box<dyn std::error::Error + Send + Sync>>
+where
+    P: Fn(&(dyn std::error::Error + Send + Sync + 'static)) -> bool,
+{
+    let index = errors.iter().position(|e| predicate(&**e))?;
+    Some(errors.remove(index))
+}
+
+/// Return the kind of io error which best matches the OS error, if any, that
+/// explains err.
+fn os_error_kind(err: &OurError) -> Option<std::io::ErrorKind> {
+    #[cfg(feature = "nix")]
+    {
+        if let Some(errno) = err
+            .constituents()
+            .find_map(|c| c.downcast_ref::<nix::Error>())
+        {
+            return Some(std::io::Error::from_raw_os_error(*errno as i32).kind());
+        }
+    }
+    err.io_error_kind()
+}
+
+// Convert into an io error, for APIs which must return one, with the kind
+// that best matches the kind of this error. The OurError itself is the
+// io error's inner error, and can be recovered with get_ref() or
+// into_inner() and a downcast.
+impl From<OurError> for std::io::Error {
+    fn from(err: OurError) -> std::io::Error {
+        let kind = match err.kind() {
+            OurErrorKind::InvalidArgument { .. } => std::io::ErrorKind::InvalidInput,
+            OurErrorKind::IoctlError { errno, .. } => {
+                std::io::Error::from_raw_os_error(*errno).kind()
+            }
+            OurErrorKind::MetadataIoError { .. } | OurErrorKind::IoError => {
+                os_error_kind(&err).unwrap_or(std::io::ErrorKind::Other)
+            }
+            OurErrorKind::ContextInitError
+            | OurErrorKind::IoctlResultTooLarge { .. }
+            | OurErrorKind::Other { .. } => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+// Display only the message associated w/ the specifics.
+// Consider the rest to be management baggage.
+impl std::fmt::Display for OurError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.specifics)
+    }
+}
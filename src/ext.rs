@@ -0,0 +1,58 @@
+use crate::error::OurError;
+use crate::kind::OurErrorKind;
+
+/// Methods for extending or sequencing the error of a Result, which is
+/// either an OurError or a foreign error. The kind of the new error is
+/// obtained only if the Result is an error.
+pub trait ResultExt<T> {
+    /// Replace the error with a new error of the given kind, which has the
+    /// original error as its constituent. The new error further explains
+    /// the original.
+    fn extend_with<F>(self, kind: F) -> Result<T, OurError>
+    where
+        F: FnOnce() -> OurErrorKind;
+
+    /// Replace the error with a new error of the given kind, which has the
+    /// original error as its previous error. The new error occurred after
+    /// the original.
+    fn followed_by<F>(self, kind: F) -> Result<T, OurError>
+    where
+        F: FnOnce() -> OurErrorKind;
+}
+
+// Since an OurError is an Error, a single implementation suffices; for an
+// OurError, these are equivalent to set_extension() and set_subsequent().
+// A match is used rather than map_err() so that the new error's backtrace
+// does not include the frames of map_err().
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn extend_with<F>(self, kind: F) -> Result<T, OurError>
+    where
+        F: FnOnce() -> OurErrorKind,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                let mut extension = OurError::new(kind());
+                extension.add_constituent(Box::new(err));
+                Err(extension)
+            }
+        }
+    }
+
+    fn followed_by<F>(self, kind: F) -> Result<T, OurError>
+    where
+        F: FnOnce() -> OurErrorKind,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                let mut subsequent = OurError::new(kind());
+                subsequent.add_previous(Box::new(err));
+                Err(subsequent)
+            }
+        }
+    }
+}
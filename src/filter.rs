@@ -0,0 +1,149 @@
+use backtrace::{Backtrace, BacktraceFrame};
+
+// The crates whose frames make up the capture machinery
+const CAPTURE_CRATES: &[&str] = &["backtrace", "rust_error_management"];
+
+// The crates whose frames make up the runtime
+const RUNTIME_CRATES: &[&str] = &["std", "core", "alloc"];
+
+// The function in which the runtime invokes main(), a spawned thread's
+// closure or a test; all the frames below it belong to the runtime. It is
+// generic, so its name may be followed by its type arguments.
+const RUNTIME_ENTRY: &str = "__rust_begin_short_backtrace";
+
+/// Return the name of the crate to which a demangled path belongs,
+/// e.g., "std" for "std::io::Error".
+fn crate_of(path: &str) -> &str {
+    let path = path.trim_start_matches(['<', '&', '*'].as_ref());
+    let path = path.trim_start_matches("dyn ").trim_start_matches("mut ");
+    path.split("::").next().unwrap_or(path)
+}
+
+/// Return the names of the crates to which a demangled symbol name may
+/// belong. The function of a trait implementation, e.g.,
+/// "<alloc::vec::Vec<T> as core::fmt::Debug>::fmt",
+/// may belong to the crate of either the type or the trait.
+fn crates_of(name: &str) -> Vec<&str> {
+    match name.find(" as ") {
+        Some(index) if name.starts_with('<') => {
+            vec![crate_of(&name[..index]), crate_of(&name[index + 4..])]
+        }
+        _ => vec![crate_of(name)],
+    }
+}
+
+/// Return the demangled names, without hashes, of the symbols of a frame.
+/// A frame may have several symbols, because of inlining.
+fn names(frame: &BacktraceFrame) -> Vec<String> {
+    frame
+        .symbols()
+        .iter()
+        .filter_map(|symbol| symbol.name().map(|name| format!("{:#}", name)))
+        .collect()
+}
+
+/// Return true if every symbol of the frame may belong to one of crates.
+/// A frame without any symbols belongs to no crate.
+fn belongs_to(frame: &BacktraceFrame, crates: &[&str]) -> bool {
+    let names = names(frame);
+    !names.is_empty()
+        && names
+            .iter()
+            .all(|name| crates_of(name).iter().any(|c| crates.contains(c)))
+}
+
+#[derive(Clone, Debug)]
+/// A filter which removes uninteresting frames from a backtrace before it is
+/// rendered.
+///
+/// By default, the frames of the capture machinery at the top of the
+/// backtrace, i.e., those of the backtrace crate and of this crate, and the
+/// frames of the runtime at the bottom, i.e., those of std that invoke main,
+/// are removed. Optionally, only the frames of a list of crates are kept.
+pub struct FrameFilter {
+    skip_capture_frames: bool,
+    skip_runtime_frames: bool,
+    crates: Option<Vec<String>>,
+}
+
+impl Default for FrameFilter {
+    fn default() -> FrameFilter {
+        FrameFilter::new()
+    }
+}
+
+impl FrameFilter {
+    /// Create a filter which removes the capture machinery and runtime
+    /// frames.
+    pub fn new() -> FrameFilter {
+        FrameFilter {
+            skip_capture_frames: true,
+            skip_runtime_frames: true,
+            crates: None,
+        }
+    }
+
+    /// Set whether the capture machinery frames at the top of the
+    /// backtrace should be removed.
+    pub fn skip_capture_frames(mut self, skip: bool) -> FrameFilter {
+        self.skip_capture_frames = skip;
+        self
+    }
+
+    /// Set whether the runtime frames at the bottom of the backtrace should
+    /// be removed.
+    pub fn skip_runtime_frames(mut self, skip: bool) -> FrameFilter {
+        self.skip_runtime_frames = skip;
+        self
+    }
+
+    /// Keep only the frames which have a symbol belonging to one of the
+    /// given crates. The name of a crate is as it appears in paths, e.g.,
+    /// "rust_error_management".
+    pub fn only_crates<I, S>(mut self, crates: I) -> FrameFilter
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.crates = Some(crates.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Apply the filter to a resolved backtrace, obtaining a new backtrace.
+    pub fn apply(&self, backtrace: &Backtrace) -> Backtrace {
+        let mut frames = backtrace.frames();
+
+        if self.skip_capture_frames {
+            let top = frames
+                .iter()
+                .take_while(|frame| belongs_to(frame, CAPTURE_CRATES))
+                .count();
+            frames = &frames[top..];
+        }
+
+        if self.skip_runtime_frames {
+            if let Some(entry) = frames
+                .iter()
+                .position(|frame| names(frame).iter().any(|name| name.contains(RUNTIME_ENTRY)))
+            {
+                frames = &frames[..entry];
+            }
+            let bottom = frames
+                .iter()
+                .rev()
+                .take_while(|frame| belongs_to(frame, RUNTIME_CRATES))
+                .count();
+            frames = &frames[..frames.len() - bottom];
+        }
+
+        let frames = frames.iter().filter(|frame| match &self.crates {
+            Some(crates) => names(frame)
+                .iter()
+                .flat_map(|name| crates_of(name))
+                .any(|name| crates.iter().any(|c| c == name)),
+            None => true,
+        });
+
+        Backtrace::from(frames.cloned().collect::<Vec<_>>())
+    }
+}
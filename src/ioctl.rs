@@ -0,0 +1,152 @@
+// The type of all devicemapper ioctls, i.e., bits 8-15 of the request code
+const DM_IOCTL: u64 = 0xfd;
+
+// The size of struct dm_ioctl, the argument of every devicemapper ioctl,
+// i.e., bits 16-29 of the request code
+const DM_IOCTL_SIZE: u64 = 312;
+
+// The direction of every devicemapper ioctl, _IOC_READ | _IOC_WRITE, i.e.,
+// bits 30-31 of the request code. Architectures with a three-bit direction
+// field, e.g., powerpc and mips, encode it as 6 << 29, which is the same.
+const DM_IOCTL_DIRECTION: u64 = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// A devicemapper ioctl command, identified by its number, i.e., bits 0-7
+/// of the request code
+#[repr(u8)]
+pub enum IoctlCommand {
+    /// DM_VERSION
+    Version = 0,
+    /// DM_REMOVE_ALL
+    RemoveAll = 1,
+    /// DM_LIST_DEVICES
+    ListDevices = 2,
+    /// DM_DEV_CREATE
+    DevCreate = 3,
+    /// DM_DEV_REMOVE
+    DevRemove = 4,
+    /// DM_DEV_RENAME
+    DevRename = 5,
+    /// DM_DEV_SUSPEND
+    DevSuspend = 6,
+    /// DM_DEV_STATUS
+    DevStatus = 7,
+    /// DM_DEV_WAIT
+    DevWait = 8,
+    /// DM_TABLE_LOAD
+    TableLoad = 9,
+    /// DM_TABLE_CLEAR
+    TableClear = 10,
+    /// DM_TABLE_DEPS
+    TableDeps = 11,
+    /// DM_TABLE_STATUS
+    TableStatus = 12,
+    /// DM_LIST_VERSIONS
+    ListVersions = 13,
+    /// DM_TARGET_MSG
+    TargetMsg = 14,
+    /// DM_DEV_SET_GEOMETRY
+    DevSetGeometry = 15,
+    /// DM_DEV_ARM_POLL
+    DevArmPoll = 16,
+    /// DM_GET_TARGET_VERSION
+    GetTargetVersion = 17,
+}
+
+// The commands, indexed by their numbers, as in linux/dm-ioctl.h
+const COMMANDS: &[(IoctlCommand, &str)] = &[
+    (IoctlCommand::Version, "DM_VERSION"),
+    (IoctlCommand::RemoveAll, "DM_REMOVE_ALL"),
+    (IoctlCommand::ListDevices, "DM_LIST_DEVICES"),
+    (IoctlCommand::DevCreate, "DM_DEV_CREATE"),
+    (IoctlCommand::DevRemove, "DM_DEV_REMOVE"),
+    (IoctlCommand::DevRename, "DM_DEV_RENAME"),
+    (IoctlCommand::DevSuspend, "DM_DEV_SUSPEND"),
+    (IoctlCommand::DevStatus, "DM_DEV_STATUS"),
+    (IoctlCommand::DevWait, "DM_DEV_WAIT"),
+    (IoctlCommand::TableLoad, "DM_TABLE_LOAD"),
+    (IoctlCommand::TableClear, "DM_TABLE_CLEAR"),
+    (IoctlCommand::TableDeps, "DM_TABLE_DEPS"),
+    (IoctlCommand::TableStatus, "DM_TABLE_STATUS"),
+    (IoctlCommand::ListVersions, "DM_LIST_VERSIONS"),
+    (IoctlCommand::TargetMsg, "DM_TARGET_MSG"),
+    (IoctlCommand::DevSetGeometry, "DM_DEV_SET_GEOMETRY"),
+    (IoctlCommand::DevArmPoll, "DM_DEV_ARM_POLL"),
+    (IoctlCommand::GetTargetVersion, "DM_GET_TARGET_VERSION"),
+];
+
+impl IoctlCommand {
+    /// The number of the command
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// The name of the command, as in linux/dm-ioctl.h, e.g., "DM_TABLE_LOAD"
+    pub fn name(self) -> &'static str {
+        COMMANDS[self.number() as usize].1
+    }
+}
+
+impl std::fmt::Display for IoctlCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+/// The request code passed to ioctl(), which encodes the devicemapper
+/// command along with the size of the argument and the direction of the
+/// transfer.
+pub struct IoctlRequest(pub u64);
+
+impl IoctlRequest {
+    /// The devicemapper command encoded by the request code, or None if the
+    /// request code is not for a known devicemapper command.
+    pub fn command(self) -> Option<IoctlCommand> {
+        if (self.0 >> 8) & 0xff != DM_IOCTL {
+            return None;
+        }
+        COMMANDS
+            .get((self.0 & 0xff) as usize)
+            .map(|(command, _)| *command)
+    }
+}
+
+// The request code for a command is _IOWR(DM_IOCTL, number, struct dm_ioctl),
+// as in linux/dm-ioctl.h, which is the same on every Linux architecture.
+impl From<IoctlCommand> for IoctlRequest {
+    fn from(command: IoctlCommand) -> IoctlRequest {
+        IoctlRequest(
+            DM_IOCTL_DIRECTION << 30
+                | DM_IOCTL_SIZE << 16
+                | DM_IOCTL << 8
+                | u64::from(command.number()),
+        )
+    }
+}
+
+// Display the name of the command, if known, otherwise the raw request code.
+impl std::fmt::Display for IoctlRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.command() {
+            Some(command) => write!(f, "{}", command),
+            None => write!(f, "{:#x}", self.0),
+        }
+    }
+}
+
+/// Return the text describing errno, as for strerror(), e.g., "Device or
+/// resource busy" for EBUSY.
+pub(crate) fn strerror(errno: i32) -> String {
+    let text = std::io::Error::from_raw_os_error(errno).to_string();
+    // std appends " (os error N)" to the text.
+    match text.rfind(" (os error ") {
+        Some(index) => text[..index].to_string(),
+        None => text,
+    }
+}
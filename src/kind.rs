@@ -0,0 +1,144 @@
+use rust_error_management_derive::ErrorKind;
+
+use crate::capture::BacktraceCapture;
+use crate::device::DeviceInfo;
+use crate::ioctl::{strerror, IoctlRequest};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// A stable code identifying a kind of error, for use by support engineers
+/// and scripts, which is displayed as the prefix and the zero-padded number,
+/// e.g., "DM-0003".
+pub struct ErrorCode {
+    prefix: &'static str,
+    number: u32,
+}
+
+impl ErrorCode {
+    /// Create a code from a prefix, which may be empty, and a number.
+    pub const fn new(prefix: &'static str, number: u32) -> ErrorCode {
+        ErrorCode { prefix, number }
+    }
+
+    /// The prefix, which identifies the family of errors
+    pub fn prefix(&self) -> &'static str {
+        self.prefix
+    }
+
+    /// The number, which identifies the kind within its family
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.prefix.is_empty() {
+            write!(f, "{:04}", self.number)
+        } else {
+            write!(f, "{}-{:04}", self.prefix, self.number)
+        }
+    }
+}
+
+/// Metadata which a kind of error declares about itself.
+/// This can be derived, together with Display, with `#[derive(ErrorKind)]`.
+pub trait ErrorKind: std::fmt::Debug + std::fmt::Display {
+    /// A stable name identifying the kind, e.g., the name of its variant
+    fn name(&self) -> &'static str;
+
+    /// A stable code identifying the kind, which must never change once
+    /// assigned
+    fn code(&self) -> ErrorCode;
+
+    /// Whether a backtrace should be captured for an error of this kind.
+    /// None, the default, means the kind has no preference. The global
+    /// policy, if set, overrides this.
+    fn backtrace_capture(&self) -> Option<BacktraceCapture> {
+        None
+    }
+}
+
+#[derive(Debug, ErrorKind)]
+// One can check equality of Kinds only if all their constituents can be
+// checked for equality.
+#[derive(Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "tag")
+)]
+/// Distinguishes among the different errors that can be encountered.
+// Invalid arguments are expected user mistakes, which never need a
+// backtrace, while failures to initialize a context or of an ioctl almost
+// always do.
+// The codes are part of the stable interface, and must never be changed or
+// reused.
+#[code(prefix = "DM")]
+#[non_exhaustive]
+pub enum OurErrorKind {
+    /// Raised when a devicemapper context is not initialized
+    #[error("DM context not initialized")]
+    #[code(1)]
+    #[backtrace(enabled)]
+    ContextInitError,
+    /// Raised when any method receives an argument it can not handle
+    #[error("invalid argument: {description}")]
+    #[code(2)]
+    #[backtrace(disabled)]
+    InvalidArgument {
+        /// What was wrong with the argument
+        description: String,
+    },
+    /// ioctl failure, recording the request and the errno it returned, from
+    /// which the text describing it is derived when displayed.
+    /// OurError::ioctl() also attaches the corresponding OS error as a
+    /// constituent.
+    #[error(
+        "ioctl {request} failed: {} (errno {errno}), device info: {device_info}",
+        strerror(*errno)
+    )]
+    #[code(3)]
+    #[backtrace(enabled)]
+    IoctlError {
+        /// The device the ioctl was issued for
+        device_info: Box<DeviceInfo>,
+        /// The request code the ioctl was issued with
+        request: IoctlRequest,
+        /// The raw errno value returned by the ioctl
+        errno: i32,
+    },
+    /// ioctl result is too large, recording the size of the buffer the
+    /// result required and the largest buffer the request can be given
+    #[error(
+        "ioctl {request} result of {requested} bytes is too large for maximum buffer size {maximum} bytes"
+    )]
+    #[code(4)]
+    IoctlResultTooLarge {
+        /// The request code the ioctl was issued with
+        request: IoctlRequest,
+        /// The size of the buffer the result required, in bytes
+        requested: u64,
+        /// The size of the largest buffer the request can be given, in bytes
+        maximum: u64,
+    },
+    /// Failed to get metadata. OurError::metadata_io() also attaches the
+    /// io error as a constituent.
+    #[error("failed to stat metadata for device at {}", path.to_string_lossy())]
+    #[code(5)]
+    MetadataIoError {
+        /// The path of the device node whose metadata was requested
+        path: std::path::PathBuf,
+    },
+    /// An io or system call failed where no more specific kind applies.
+    /// The failure itself is attached as a constituent.
+    #[error("I/O error")]
+    #[code(6)]
+    IoError,
+    /// Any other error, described only by a message
+    #[error("{description}")]
+    #[code(7)]
+    Other {
+        /// The message describing the error
+        description: String,
+    },
+}
@@ -0,0 +1,70 @@
+//! Error management for devicemapper-style libraries.
+//!
+//! An `OurError` combines an `OurErrorKind`, which distinguishes among the
+//! different errors, with a backtrace captured where the error was created
+//! and optional suberrors. A suberror records how some other error relates to
+//! this one: either it is a constituent error, which this error further
+//! explains, or it is a previous error, which occurred earlier and presumably
+//! caused the code that encountered this error to be run. An error may have
+//! any number of constituent and previous errors, so that errors form a tree.
+//! Every suberror must be `Send` and `Sync`, so that an `OurError` is, too.
+//!
+//! `ResultExt` extends or sequences the error of a `Result`. An
+//! `std::io::Error`, a `String`, and, with the `nix` feature enabled, a
+//! `nix::Error` can be converted into an `OurError`, so that `?` may be used,
+//! and an `OurError` can be converted into an `std::io::Error`.
+//!
+//! Whether a backtrace is captured is determined at runtime, by the
+//! `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment variables, or by
+//! `set_backtrace_capture()`. If none of these is set, each kind of error
+//! declares its own policy through the `ErrorKind` trait, which, together
+//! with `Display`, can be derived with `#[derive(ErrorKind)]`. A
+//! `FrameFilter` removes uninteresting frames from a backtrace before it is
+//! rendered.
+//!
+//! Every kind has a stable `ErrorCode`, e.g., "DM-0003", which is included
+//! in reports and serialized errors.
+//!
+//! `OurError::chain()` walks an error and its sources, yielding each together
+//! with its `Relation` to its parent. `OurError::tree()` walks the whole
+//! tree, and `OurError::report()` renders it for users. `OurError::find()`
+//! and `OurError::find_kind()` search the tree for an error of some type or
+//! kind.
+//!
+//! With the `serde` feature enabled, `OurError` and `OurErrorKind` implement
+//! `serde::Serialize`; `Serialized` also allows including backtraces. A
+//! serialized error, e.g., one received from another process, can be
+//! deserialized into a `RemoteError`.
+
+// Allow the code generated by the derive macro, which refers to this crate
+// by name, to be used within this crate.
+extern crate self as rust_error_management;
+
+mod capture;
+mod chain;
+mod device;
+mod error;
+mod ext;
+mod filter;
+mod ioctl;
+mod kind;
+#[cfg(feature = "serde")]
+mod remote;
+mod report;
+#[cfg(feature = "serde")]
+mod serialize;
+
+pub use crate::capture::{backtrace_capture, set_backtrace_capture, BacktraceCapture};
+pub use crate::chain::{Chain, Link, Relation, Tree};
+pub use crate::device::DeviceInfo;
+pub use crate::error::{OurError, Suberror};
+pub use crate::ext::ResultExt;
+pub use crate::filter::FrameFilter;
+pub use crate::ioctl::{IoctlCommand, IoctlRequest};
+pub use crate::kind::{ErrorCode, ErrorKind, OurErrorKind};
+#[cfg(feature = "serde")]
+pub use crate::remote::RemoteError;
+pub use crate::report::{Backtraces, Report};
+#[cfg(feature = "serde")]
+pub use crate::serialize::Serialized;
+pub use rust_error_management_derive::ErrorKind;
@@ -0,0 +1,211 @@
+use std::error::Error;
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+
+use crate::chain::{find_in, find_kind_in, last_along, Chain, Relation, Tree};
+use crate::kind::OurErrorKind;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+// A kind which may have been serialized by a different version of this
+// library, and so may not be recognized.
+enum MaybeKind {
+    Known(OurErrorKind),
+    Unknown(IgnoredAny),
+}
+
+#[derive(Deserialize)]
+// A single error as serialized by Serialized. The relation of the root and
+// any backtrace are ignored.
+struct Node {
+    #[serde(default)]
+    relation: Option<Relation>,
+    #[serde(default)]
+    type_name: Option<String>,
+    message: String,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    code_number: Option<u32>,
+    #[serde(default)]
+    kind: Option<MaybeKind>,
+    #[serde(default)]
+    sources: Vec<Node>,
+}
+
+#[derive(Debug)]
+/// An error reconstructed from the serialized representation of an error
+/// which may have been raised in another process.
+///
+/// The relations among the errors are preserved. The kind of an error which
+/// was an OurError is recovered if it is recognized, and its code is
+/// preserved even if it is not; all other errors are represented only by
+/// their message and the name of their type.
+pub struct RemoteError {
+    type_name: Option<String>,
+    message: String,
+    code: Option<String>,
+    code_number: Option<u32>,
+    kind: Option<OurErrorKind>,
+    constituents: Vec<RemoteError>,
+    previous: Vec<RemoteError>,
+}
+
+// A RemoteError, like an OurError, must be Send and Sync.
+const _: () = {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    let _ = assert_send_sync::<RemoteError>;
+};
+
+impl From<Node> for RemoteError {
+    fn from(node: Node) -> RemoteError {
+        let mut constituents = vec![];
+        let mut previous = vec![];
+        for source in node.sources {
+            match source.relation {
+                Some(Relation::Previous) => previous.push(RemoteError::from(source)),
+                // A source of unknown relation is treated as a constituent,
+                // as for the source of a foreign error.
+                _ => constituents.push(RemoteError::from(source)),
+            }
+        }
+        RemoteError {
+            type_name: node.type_name,
+            message: node.message,
+            code: node.code,
+            code_number: node.code_number,
+            kind: match node.kind {
+                Some(MaybeKind::Known(kind)) => Some(kind),
+                _ => None,
+            },
+            constituents,
+            previous,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RemoteError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RemoteError, D::Error> {
+        Node::deserialize(deserializer).map(RemoteError::from)
+    }
+}
+
+impl RemoteError {
+    /// Return the kind of this error, if it was an OurError and its kind
+    /// was recognized.
+    pub fn kind(&self) -> Option<&OurErrorKind> {
+        self.kind.as_ref()
+    }
+
+    /// Return the code of the original error, formatted as for ErrorCode,
+    /// if it was an OurError.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Return the number of the code of the original error, if it was an
+    /// OurError.
+    pub fn code_number(&self) -> Option<u32> {
+        self.code_number
+    }
+
+    /// Return the name of the type of the original error, if it was known.
+    pub fn type_name(&self) -> Option<&str> {
+        self.type_name.as_deref()
+    }
+
+    /// Obtain the first immediate previous error, if there is one
+    pub fn previous(&self) -> Option<&RemoteError> {
+        self.previous.first()
+    }
+
+    /// Obtain the first immediate constituent error, if there is one
+    pub fn constituent(&self) -> Option<&RemoteError> {
+        self.constituents.first()
+    }
+
+    /// Obtain all the immediate previous errors, in their original order
+    pub fn previous_errors(&self) -> impl Iterator<Item = &RemoteError> {
+        self.previous.iter()
+    }
+
+    /// Obtain all the immediate constituent errors, in their original order
+    pub fn constituents(&self) -> impl Iterator<Item = &RemoteError> {
+        self.constituents.iter()
+    }
+
+    /// Obtain all the immediate suberrors of this error, together with
+    /// their relation to this error. Constituents precede previous errors.
+    pub fn suberrors(&self) -> impl Iterator<Item = (Relation, &(dyn Error + 'static))> {
+        self.constituents()
+            .map(|c| (Relation::Constituent, c as &(dyn Error + 'static)))
+            .chain(
+                self.previous_errors()
+                    .map(|p| (Relation::Previous, p as &(dyn Error + 'static))),
+            )
+    }
+
+    /// Iterate over this error and its successive sources, obtaining the
+    /// relation of each error to its parent, as for OurError::chain().
+    pub fn chain(&self) -> Chain<'_> {
+        Chain::new(self)
+    }
+
+    /// Iterate depth-first over this error and all the errors it is built
+    /// from, as for OurError::tree().
+    pub fn tree(&self) -> Tree<'_> {
+        Tree::new(self)
+    }
+
+    /// Return the deepest explanation of this error, i.e., the error reached
+    /// by following only first constituents, as for OurError::root_cause().
+    pub fn root_cause(&self) -> &(dyn Error + 'static) {
+        last_along(self, Relation::Constituent)
+    }
+
+    /// Return the earliest error in the sequence of errors which ends with
+    /// this one, i.e., the error reached by following only first previous
+    /// errors, as for OurError::earliest().
+    pub fn earliest(&self) -> &(dyn Error + 'static) {
+        last_along(self, Relation::Previous)
+    }
+
+    /// Return the first error of type T among this error and all the errors
+    /// it is built from, in the order of tree(), as for OurError::find().
+    /// Every error it is built from is also a RemoteError, so find_kind() is
+    /// usually more useful.
+    pub fn find<T: Error + 'static>(&self) -> Option<&T> {
+        find_in(self, None)
+    }
+
+    /// Return the first error of type T among this error and the errors
+    /// reached from it by following only the links of the given relation,
+    /// as for OurError::find_along().
+    pub fn find_along<T: Error + 'static>(&self, relation: Relation) -> Option<&T> {
+        find_in(self, Some(relation))
+    }
+
+    /// Return the first recognized kind satisfying predicate among the kinds
+    /// of this error and all the errors it is built from, in the order of
+    /// tree(), as for OurError::find_kind().
+    pub fn find_kind<P>(&self, predicate: P) -> Option<&OurErrorKind>
+    where
+        P: FnMut(&OurErrorKind) -> bool,
+    {
+        find_kind_in(self, predicate)
+    }
+}
+
+impl Error for RemoteError {
+    // The same precedence as for OurError.
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.suberrors().next().map(|(_, e)| e)
+    }
+}
+
+impl std::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
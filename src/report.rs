@@ -0,0 +1,169 @@
+use std::error::Error;
+use std::fmt::Write;
+
+use backtrace::Backtrace;
+
+use crate::chain::Tree;
+use crate::error::OurError;
+use crate::filter::FrameFilter;
+#[cfg(feature = "serde")]
+use crate::remote::RemoteError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Which of the errors in a report should have their backtraces rendered
+pub enum Backtraces {
+    /// Render no backtraces
+    Omit,
+    /// Render only the backtrace of the error at the root of the report
+    Head,
+    /// Render the backtrace of every error that has one
+    All,
+}
+
+#[derive(Clone, Debug)]
+/// A multi-line, human-readable rendering of an error and all the errors it
+/// is built from. Each error is on its own line, prefixed with its code, if
+/// it has one, indented according to its depth, and labelled with its
+/// relation to its parent, i.e., "caused by" for a constituent and "preceded
+/// by" for a previous error. Backtraces, if rendered, are first filtered by
+/// a FrameFilter, and frames in common with the backtrace of the error above
+/// are elided.
+pub struct Report<'a> {
+    error: &'a (dyn Error + 'static),
+    backtraces: Backtraces,
+    filter: FrameFilter,
+    elide_common_frames: bool,
+}
+
+impl<'a> Report<'a> {
+    /// Create a report on error, rendering no backtraces.
+    pub fn new(error: &'a (dyn Error + 'static)) -> Report<'a> {
+        Report {
+            error,
+            backtraces: Backtraces::Omit,
+            filter: FrameFilter::default(),
+            elide_common_frames: true,
+        }
+    }
+
+    /// Set which errors should have their backtraces rendered.
+    pub fn backtraces(mut self, backtraces: Backtraces) -> Report<'a> {
+        self.backtraces = backtraces;
+        self
+    }
+
+    /// Set the filter to apply to backtraces before they are rendered.
+    pub fn frame_filter(mut self, filter: FrameFilter) -> Report<'a> {
+        self.filter = filter;
+        self
+    }
+
+    /// Set whether frames at the bottom of an error's backtrace which are
+    /// the same as those in the backtrace of the error above it should be
+    /// replaced by a count. They are, by default, since when an error is
+    /// extended, the outer frames are usually repeated.
+    pub fn elide_common_frames(mut self, elide: bool) -> Report<'a> {
+        self.elide_common_frames = elide;
+        self
+    }
+}
+
+/// Write text to f, prefixing each line with indent spaces.
+fn write_indented(f: &mut std::fmt::Formatter, text: &str, indent: usize) -> std::fmt::Result {
+    for line in text.lines() {
+        writeln!(f, "{:indent$}{}", "", line, indent = indent)?;
+    }
+    Ok(())
+}
+
+/// Return the code of the error, if it has one.
+fn code_of(error: &(dyn Error + 'static)) -> Option<String> {
+    if let Some(ours) = error.downcast_ref::<OurError>() {
+        return Some(ours.code().to_string());
+    }
+    #[cfg(feature = "serde")]
+    {
+        if let Some(remote) = error.downcast_ref::<RemoteError>() {
+            return remote.code().map(str::to_string);
+        }
+    }
+    None
+}
+
+/// Return the number of frames at the bottom of backtrace which are the same
+/// as those at the bottom of other.
+fn common_frames(backtrace: &Backtrace, other: &Backtrace) -> usize {
+    backtrace
+        .frames()
+        .iter()
+        .rev()
+        .zip(other.frames().iter().rev())
+        .take_while(|(a, b)| a.ip() == b.ip())
+        .count()
+}
+
+impl std::fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // The filtered backtraces of the ancestors of the current error,
+        // indexed by depth
+        let mut ancestors: Vec<Option<Backtrace>> = vec![];
+
+        for link in Tree::new(self.error) {
+            let indent = 2 * link.depth;
+            let label = match code_of(link.error) {
+                Some(code) => format!("[{}] {}", code, link.error),
+                None => link.error.to_string(),
+            };
+            match link.relation {
+                None => writeln!(f, "{}", label)?,
+                Some(relation) => {
+                    writeln!(f, "{:indent$}{}: {}", "", relation, label, indent = indent)?
+                }
+            }
+
+            let render = match self.backtraces {
+                Backtraces::Omit => false,
+                Backtraces::Head => link.depth == 0,
+                Backtraces::All => true,
+            };
+            let backtrace = link
+                .error
+                .downcast_ref::<OurError>()
+                .filter(|_| render)
+                .and_then(|e| e.filtered_backtrace(&self.filter));
+
+            ancestors.truncate(link.depth);
+            if let Some(backtrace) = backtrace.as_ref() {
+                // The frames shared with the nearest ancestor that has a
+                // backtrace
+                let common = ancestors
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .next()
+                    .filter(|_| self.elide_common_frames)
+                    .map(|ancestor| common_frames(backtrace, ancestor))
+                    .unwrap_or(0);
+                let frames = backtrace.frames();
+                let own = Backtrace::from(frames[..frames.len() - common].to_vec());
+
+                writeln!(f, "{:indent$}backtrace:", "", indent = indent + 2)?;
+                let mut text = String::new();
+                write!(text, "{:?}", own)?;
+                write_indented(f, &text, indent + 4)?;
+                if common > 0 {
+                    writeln!(
+                        f,
+                        "{:indent$}... {} {} in common with the error above",
+                        "",
+                        common,
+                        if common == 1 { "frame" } else { "frames" },
+                        indent = indent + 4
+                    )?;
+                }
+            }
+            ancestors.push(backtrace);
+        }
+        Ok(())
+    }
+}
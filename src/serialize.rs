@@ -0,0 +1,172 @@
+use std::error::Error;
+
+use backtrace::Backtrace;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::chain::{suberrors_of, Relation};
+use crate::error::OurError;
+use crate::remote::RemoteError;
+
+/// Return the name of the type of error, if it can be determined.
+/// The concrete type of a boxed error can only be discovered by trying to
+/// downcast it, so only OurError, the common std error types, and, with the
+/// nix feature enabled, nix::Error are recognized. The names are part of the
+/// serialized format, so they are fixed here rather than obtained from
+/// std::any::type_name(), whose output may change with the toolchain and
+/// includes private module paths.
+pub(crate) fn type_name_of(error: &(dyn Error + 'static)) -> Option<&'static str> {
+    macro_rules! recognize {
+        ($($(#[$attr:meta])* $t:ty => $name:expr),*) => {
+            $(
+                $(#[$attr])*
+                {
+                    if error.is::<$t>() {
+                        return Some($name);
+                    }
+                }
+            )*
+        };
+    }
+    recognize!(
+        OurError => "OurError",
+        std::io::Error => "std::io::Error",
+        std::fmt::Error => "std::fmt::Error",
+        std::num::ParseIntError => "std::num::ParseIntError",
+        std::num::ParseFloatError => "std::num::ParseFloatError",
+        std::num::TryFromIntError => "std::num::TryFromIntError",
+        std::str::Utf8Error => "std::str::Utf8Error",
+        std::string::FromUtf8Error => "std::string::FromUtf8Error",
+        std::ffi::NulError => "std::ffi::NulError",
+        #[cfg(feature = "nix")]
+        nix::Error => "nix::Error"
+    );
+    None
+}
+
+#[derive(Serialize)]
+/// A single resolved symbol of a backtrace frame
+struct Symbol {
+    name: Option<String>,
+    filename: Option<String>,
+    lineno: Option<u32>,
+}
+
+#[derive(Serialize)]
+/// A single backtrace frame, with its instruction pointer formatted in hex
+struct Frame {
+    ip: String,
+    symbols: Vec<Symbol>,
+}
+
+fn frames(backtrace: &Backtrace) -> Vec<Frame> {
+    backtrace
+        .frames()
+        .iter()
+        .map(|frame| Frame {
+            ip: format!("{:?}", frame.ip()),
+            symbols: frame
+                .symbols()
+                .iter()
+                .map(|symbol| Symbol {
+                    name: symbol.name().map(|n| n.to_string()),
+                    filename: symbol.filename().map(|f| f.to_string_lossy().into_owned()),
+                    lineno: symbol.lineno(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+/// A serializable representation of an error and all the errors it is built
+/// from. A RemoteError is serialized as the error it was deserialized from,
+/// less its backtraces.
+///
+/// Each error is serialized as a map with the following entries:
+/// * "relation": the relation to its parent, omitted for the root
+/// * "type_name": the name of the error's type, or null if unknown; this is
+///   "OurError" for an OurError, and the public path, e.g.,
+///   "std::io::Error", for a recognized std error
+/// * "message": the error's Display message
+/// * "code": the formatted code, e.g., "DM-0003", only for an OurError
+/// * "code_number": the number of the code, only for an OurError
+/// * "kind": the kind, tagged with its variant as "tag", only for an OurError
+/// * "backtrace": the resolved backtrace frames, only for an OurError and
+///   only if requested
+/// * "sources": the serialized suberrors of the error
+pub struct Serialized<'a> {
+    error: &'a (dyn Error + 'static),
+    relation: Option<Relation>,
+    backtraces: bool,
+}
+
+impl<'a> Serialized<'a> {
+    /// Create a serializable representation of error, omitting backtraces.
+    pub fn new(error: &'a (dyn Error + 'static)) -> Serialized<'a> {
+        Serialized {
+            error,
+            relation: None,
+            backtraces: false,
+        }
+    }
+
+    /// Set whether the backtrace of each OurError should be included.
+    pub fn backtraces(mut self, backtraces: bool) -> Serialized<'a> {
+        self.backtraces = backtraces;
+        self
+    }
+}
+
+impl Serialize for Serialized<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let ours = self.error.downcast_ref::<OurError>();
+        let remote = self.error.downcast_ref::<RemoteError>();
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(relation) = self.relation {
+            map.serialize_entry("relation", &relation)?;
+        }
+        match remote {
+            Some(remote) => map.serialize_entry("type_name", &remote.type_name())?,
+            None => map.serialize_entry("type_name", &type_name_of(self.error))?,
+        }
+        map.serialize_entry("message", &self.error.to_string())?;
+        if let Some(ours) = ours {
+            map.serialize_entry("code", &ours.code().to_string())?;
+            map.serialize_entry("code_number", &ours.code().number())?;
+            map.serialize_entry("kind", ours.kind())?;
+            if self.backtraces {
+                map.serialize_entry("backtrace", &ours.our_backtrace().map(frames))?;
+            }
+        }
+        if let Some(remote) = remote {
+            if let Some(code) = remote.code() {
+                map.serialize_entry("code", code)?;
+            }
+            if let Some(number) = remote.code_number() {
+                map.serialize_entry("code_number", &number)?;
+            }
+            if let Some(kind) = remote.kind() {
+                map.serialize_entry("kind", kind)?;
+            }
+        }
+        let sources = suberrors_of(self.error)
+            .into_iter()
+            .map(|(relation, error)| Serialized {
+                error,
+                relation: Some(relation),
+                backtraces: self.backtraces,
+            })
+            .collect::<Vec<_>>();
+        map.serialize_entry("sources", &sources)?;
+        map.end()
+    }
+}
+
+// Serialize the error and all the errors it is built from, omitting
+// backtraces. Use Serialized to include them.
+impl Serialize for OurError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Serialized::new(self).serialize(serializer)
+    }
+}
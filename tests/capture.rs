@@ -0,0 +1,109 @@
+use std::sync::Mutex;
+
+use rust_error_management::{
+    backtrace_capture, set_backtrace_capture, BacktraceCapture, DeviceInfo, ErrorKind,
+    IoctlRequest, OurError, OurErrorKind,
+};
+
+fn invalid_argument() -> OurErrorKind {
+    OurErrorKind::InvalidArgument {
+        description: "32".into(),
+    }
+}
+
+fn ioctl() -> OurError {
+    OurError::ioctl(DeviceInfo::new("dm-0", 253, 0), IoctlRequest(0), 16)
+}
+
+// The policy is global, so the tests which change it must not run
+// concurrently.
+static POLICY: Mutex<()> = Mutex::new(());
+
+#[test]
+fn override_disables_capture() {
+    let _guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(Some(BacktraceCapture::Disabled));
+    assert_eq!(backtrace_capture(), Some(BacktraceCapture::Disabled));
+    assert!(OurError::new(OurErrorKind::ContextInitError)
+        .our_backtrace()
+        .is_none());
+}
+
+#[test]
+fn override_enables_capture() {
+    let _guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+    assert_eq!(backtrace_capture(), Some(BacktraceCapture::Enabled));
+    let err = OurError::new(OurErrorKind::ContextInitError);
+    assert!(!err.our_backtrace().expect("").frames().is_empty());
+}
+
+#[test]
+fn kinds_declare_their_policy() {
+    assert_eq!(
+        OurErrorKind::ContextInitError.backtrace_capture(),
+        Some(BacktraceCapture::Enabled)
+    );
+    assert_eq!(
+        invalid_argument().backtrace_capture(),
+        Some(BacktraceCapture::Disabled)
+    );
+    assert_eq!(OurErrorKind::IoError.backtrace_capture(), None);
+}
+
+#[test]
+fn kind_decides_without_global_policy() {
+    let _guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(None);
+    if std::env::var_os("RUST_LIB_BACKTRACE").is_some()
+        || std::env::var_os("RUST_BACKTRACE").is_some()
+    {
+        // The environment sets the global policy.
+        return;
+    }
+    assert_eq!(backtrace_capture(), None);
+    // An invalid argument, an expected user mistake, never has a
+    // backtrace, whereas an ioctl error does.
+    assert!(OurError::new(invalid_argument()).our_backtrace().is_none());
+    assert!(ioctl().our_backtrace().is_some());
+    // A kind with no preference follows the default.
+    assert!(OurError::new(OurErrorKind::IoError)
+        .our_backtrace()
+        .is_none());
+}
+
+#[test]
+fn global_policy_overrides_kind() {
+    let _guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+    assert!(OurError::new(invalid_argument()).our_backtrace().is_some());
+    set_backtrace_capture(Some(BacktraceCapture::Disabled));
+    assert!(ioctl().our_backtrace().is_none());
+}
+
+#[test]
+fn symbols_are_resolved_on_first_access() {
+    let _guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+
+    // A backtrace which was never examined is taken apart unresolved.
+    let (_, _, backtrace) = OurError::new(OurErrorKind::ContextInitError).into_parts();
+    let backtrace = backtrace.expect("");
+    assert!(!backtrace.frames().is_empty());
+    assert!(backtrace.frames().iter().all(|f| f.symbols().is_empty()));
+
+    // Once examined, it stays resolved.
+    let err = OurError::new(OurErrorKind::ContextInitError);
+    assert!(err
+        .our_backtrace()
+        .expect("")
+        .frames()
+        .iter()
+        .any(|f| !f.symbols().is_empty()));
+    let (_, _, backtrace) = err.into_parts();
+    assert!(backtrace
+        .expect("")
+        .frames()
+        .iter()
+        .any(|f| !f.symbols().is_empty()));
+}
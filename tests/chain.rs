@@ -0,0 +1,243 @@
+use std::error::Error;
+
+use rust_error_management::{Chain, OurError, OurErrorKind, Relation};
+
+mod common;
+
+use common::{d, e, f, EBUSY};
+
+// A foreign error whose source is an OurError
+#[derive(Debug)]
+struct Wrapper(OurError);
+
+impl std::fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "wrapped")
+    }
+}
+
+impl Error for Wrapper {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn chain_distinguishes_relations() {
+    let err = d().expect_err("");
+    assert_eq!(
+        err.chain().map(|l| l.relation).collect::<Vec<_>>(),
+        vec![
+            None,
+            Some(Relation::Previous),
+            Some(Relation::Constituent),
+            Some(Relation::Constituent),
+        ]
+    );
+    assert_eq!(
+        err.chain().map(|l| l.depth).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
+}
+
+#[test]
+fn chain_continues_through_foreign_errors() {
+    let err = d().expect_err("");
+    let last = err.chain().last().expect("");
+    assert!(last.error.is::<std::io::Error>());
+    assert_eq!(last.error.to_string(), "oh no!");
+
+    // The source of a foreign error is treated as a constituent.
+    let foreign = Wrapper(d().expect_err(""));
+    let chain = Chain::new(&foreign).collect::<Vec<_>>();
+    assert_eq!(chain.len(), 5);
+    assert_eq!(chain[1].relation, Some(Relation::Constituent));
+    assert!(chain[1].error.is::<OurError>());
+    assert_eq!(chain[2].relation, Some(Relation::Previous));
+}
+
+#[test]
+fn chain_follows_sources() {
+    let err = d().expect_err("");
+    let mut source: Option<&(dyn Error + 'static)> = Some(&err);
+    for link in err.chain() {
+        assert!(std::ptr::addr_eq(
+            link.error,
+            source.expect("") as *const dyn Error
+        ));
+        source = link.error.source();
+    }
+    assert!(source.is_none());
+}
+
+#[test]
+fn constituent_takes_precedence_over_previous() {
+    let err = e().expect_err("");
+    assert!(err.constituent().expect("").is::<std::io::Error>());
+    assert!(err.previous().expect("").is::<OurError>());
+    assert!(err.source().expect("").is::<std::io::Error>());
+    assert_eq!(
+        err.chain().map(|l| l.relation).collect::<Vec<_>>(),
+        vec![None, Some(Relation::Constituent)]
+    );
+}
+
+#[test]
+fn tree_visits_constituents_first() {
+    let err = f().expect_err("");
+    assert_eq!(err.constituents().count(), 1);
+    assert_eq!(err.previous_errors().count(), 2);
+    assert_eq!(
+        err.tree()
+            .map(|l| (l.depth, l.relation))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, None),
+            (1, Some(Relation::Constituent)),
+            (2, Some(Relation::Constituent)),
+            (1, Some(Relation::Previous)),
+            (2, Some(Relation::Constituent)),
+            (1, Some(Relation::Previous)),
+            (2, Some(Relation::Constituent)),
+        ]
+    );
+    assert_eq!(
+        err.tree()
+            .filter_map(|l| l.error.downcast_ref::<OurError>())
+            .map(|e| e.code().number())
+            .collect::<Vec<_>>(),
+        vec![2, 1, 3, 3]
+    );
+}
+
+#[test]
+fn tree_of_a_chain_is_the_chain() {
+    let err = d().expect_err("");
+    assert_eq!(
+        err.tree()
+            .map(|l| (l.depth, l.relation))
+            .collect::<Vec<_>>(),
+        err.chain()
+            .map(|l| (l.depth, l.relation))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn find_searches_the_tree() {
+    let err = d().expect_err("");
+    assert_eq!(
+        err.find::<std::io::Error>().expect("").to_string(),
+        "oh no!"
+    );
+    assert_eq!(
+        err.find::<OurError>().expect("").code().to_string(),
+        "DM-0004"
+    );
+    assert!(err.find::<Wrapper>().is_none());
+}
+
+#[test]
+fn find_searches_through_foreign_errors() {
+    let mut err = OurError::new(OurErrorKind::ContextInitError);
+    err.add_constituent(Box::new(Wrapper(d().expect_err(""))));
+    assert!(err.find::<std::io::Error>().is_some());
+}
+
+#[test]
+fn find_kind_searches_the_tree() {
+    let is_ioctl = |k: &OurErrorKind| matches!(k, OurErrorKind::IoctlError { .. });
+    let many = f().expect_err("");
+    assert_eq!(
+        many.find_kind(is_ioctl).expect("").to_string(),
+        "ioctl DM_DEV_REMOVE failed: Device or resource busy (errno 16), \
+         device info: dm-0 (253:0), event 0, flags 0x0"
+    );
+    assert!(d().expect_err("").find_kind(is_ioctl).is_none());
+}
+
+#[test]
+fn find_along_follows_only_relation() {
+    // The io error explains an error which merely preceded this one.
+    let err = d().expect_err("");
+    assert!(err
+        .find_along::<std::io::Error>(Relation::Constituent)
+        .is_none());
+    assert!(err
+        .find_along::<std::io::Error>(Relation::Previous)
+        .is_none());
+
+    // An ioctl error is explained by an OS error, and preceded by the failure
+    // to initialize the context.
+    let both = e().expect_err("");
+    assert_eq!(
+        both.find_along::<std::io::Error>(Relation::Constituent)
+            .expect("")
+            .raw_os_error(),
+        Some(EBUSY)
+    );
+    assert!(both
+        .find_along::<std::io::Error>(Relation::Previous)
+        .is_none());
+}
+
+#[test]
+fn root_cause_follows_constituents() {
+    let err = OurError::new(OurErrorKind::ContextInitError);
+    assert!(std::ptr::eq(
+        err.root_cause().downcast_ref::<OurError>().expect(""),
+        &err
+    ));
+
+    // The ioctl error is explained by an OS error, not by the error which
+    // preceded it.
+    let both = e().expect_err("");
+    assert_eq!(
+        both.root_cause()
+            .downcast_ref::<std::io::Error>()
+            .expect("")
+            .raw_os_error(),
+        Some(EBUSY)
+    );
+
+    // An error with only previous errors is its own root cause.
+    let err = d().expect_err("");
+    assert_eq!(
+        err.root_cause()
+            .downcast_ref::<OurError>()
+            .expect("")
+            .code()
+            .to_string(),
+        "DM-0004"
+    );
+}
+
+#[test]
+fn root_cause_follows_foreign_sources() {
+    let mut err = OurError::new(OurErrorKind::ContextInitError);
+    err.add_constituent(Box::new(Wrapper(e().expect_err(""))));
+    assert_eq!(
+        err.root_cause().to_string(),
+        "Device or resource busy (os error 16)"
+    );
+}
+
+#[test]
+fn earliest_follows_previous_errors() {
+    let err = d().expect_err("");
+    let earliest = err.earliest().downcast_ref::<OurError>().expect("");
+    assert_eq!(earliest.to_string(), "invalid argument: 32");
+    assert_eq!(earliest.root_cause().to_string(), "oh no!");
+
+    // The first of several previous errors is followed.
+    let many = f().expect_err("");
+    let earliest = many.earliest().downcast_ref::<OurError>().expect("");
+    match earliest.kind() {
+        OurErrorKind::IoctlError { device_info, .. } => assert_eq!(device_info.name, "dm-0"),
+        _ => panic!("expected an ioctl error"),
+    }
+
+    // A constituent is not an earlier error.
+    let err = OurError::from(std::io::Error::other("io"));
+    assert!(err.earliest().is::<OurError>());
+}
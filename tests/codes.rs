@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use rust_error_management::{DeviceInfo, ErrorKind, IoctlRequest, OurErrorKind};
+
+// One instance of every kind, with the code it must always have. A kind
+// which is added must be added here; a code which is changed or reused
+// makes this test fail.
+fn kinds() -> Vec<(OurErrorKind, &'static str, u32)> {
+    vec![
+        (OurErrorKind::ContextInitError, "DM-0001", 1),
+        (
+            OurErrorKind::InvalidArgument {
+                description: "".into(),
+            },
+            "DM-0002",
+            2,
+        ),
+        (
+            OurErrorKind::IoctlError {
+                device_info: Box::new(DeviceInfo::new("", 0, 0)),
+                request: IoctlRequest(0),
+                errno: 0,
+            },
+            "DM-0003",
+            3,
+        ),
+        (
+            OurErrorKind::IoctlResultTooLarge {
+                request: IoctlRequest(0),
+                requested: 0,
+                maximum: 0,
+            },
+            "DM-0004",
+            4,
+        ),
+        (
+            OurErrorKind::MetadataIoError { path: "".into() },
+            "DM-0005",
+            5,
+        ),
+        (OurErrorKind::IoError, "DM-0006", 6),
+        (
+            OurErrorKind::Other {
+                description: "".into(),
+            },
+            "DM-0007",
+            7,
+        ),
+    ]
+}
+
+#[test]
+fn codes_are_stable() {
+    for (kind, text, number) in kinds() {
+        assert_eq!(kind.code().to_string(), text, "{:?}", kind);
+        assert_eq!(kind.code().number(), number, "{:?}", kind);
+        assert_eq!(kind.code().prefix(), "DM", "{:?}", kind);
+    }
+}
+
+#[test]
+fn codes_are_unique() {
+    let kinds = kinds();
+    let codes = kinds
+        .iter()
+        .map(|(kind, _, _)| kind.code())
+        .collect::<HashSet<_>>();
+    assert_eq!(codes.len(), kinds.len());
+}
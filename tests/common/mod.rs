@@ -0,0 +1,79 @@
+// The scenarios shared by the tests, as in examples/demo.rs. Not every test
+// uses every scenario.
+#![allow(dead_code)]
+
+use rust_error_management::{
+    DeviceInfo, IoctlCommand, IoctlRequest, OurError, OurErrorKind, ResultExt,
+};
+
+// The errnos for a missing file and a busy device
+pub const ENOENT: i32 = 2;
+pub const EBUSY: i32 = 16;
+
+// A context which could not be initialized, explained by an io error
+pub fn b() -> Result<(), OurError> {
+    let err = std::io::Error::other("oh no!");
+    let mut ours = OurError::new(OurErrorKind::ContextInitError);
+    ours.set_constituent(Box::new(err));
+    Err(ours)
+}
+
+pub fn c() -> Result<(), OurError> {
+    b()
+}
+
+// An invalid argument, explained by c(), followed by an ioctl whose result
+// was too large
+pub fn d() -> Result<(), OurError> {
+    c().extend_with(|| OurErrorKind::InvalidArgument {
+        description: "32".into(),
+    })
+    .followed_by(|| OurErrorKind::IoctlResultTooLarge {
+        request: IoctlRequest::from(IoctlCommand::ListDevices),
+        requested: 1 << 32,
+        maximum: u32::MAX.into(),
+    })
+}
+
+// An ioctl failure, explained by an errno, which happened while cleaning up
+// after an earlier failure
+pub fn e() -> Result<(), OurError> {
+    let ours = OurError::ioctl(
+        DeviceInfo::new("dm-0", 253, 0),
+        IoctlRequest::from(IoctlCommand::TableLoad),
+        EBUSY,
+    );
+    Err(c().expect_err("").set_subsequent(ours))
+}
+
+// Tearing down several devices, each of which fails independently; the
+// failures all precede the error reporting that the teardown failed.
+pub fn f() -> Result<(), OurError> {
+    let mut ours = OurError::new(OurErrorKind::InvalidArgument {
+        description: "teardown".into(),
+    });
+    for (name, minor) in &[("dm-0", 0), ("dm-1", 1)] {
+        ours.add_previous(Box::new(OurError::ioctl(
+            DeviceInfo::new(name, 253, *minor),
+            IoctlRequest::from(IoctlCommand::DevRemove),
+            EBUSY,
+        )));
+    }
+    ours.add_constituent(Box::new(c().expect_err("")));
+    Err(ours)
+}
+
+// A failure to stat a device node, which is explained by the io error
+pub fn g() -> Result<std::fs::Metadata, OurError> {
+    let path = "/dev/mapper/no-such-device";
+    std::fs::metadata(path).map_err(|err| OurError::metadata_io(path, err))
+}
+
+// Reading a device's attribute, where an io error is converted by ?
+pub fn h() -> Result<String, OurError> {
+    let text = std::fs::read_to_string("/sys/block/no-such-device/dm/name")?;
+    if text.is_empty() {
+        return Err("empty device name".into());
+    }
+    Ok(text)
+}
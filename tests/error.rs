@@ -0,0 +1,266 @@
+use rust_error_management::{
+    DeviceInfo, IoctlCommand, IoctlRequest, OurError, OurErrorKind, Relation, ResultExt, Suberror,
+};
+
+mod common;
+
+use common::{c, d, e, f, g, h, EBUSY, ENOENT};
+
+#[test]
+fn set_extension_keeps_existing_constituents() {
+    let low = c().expect_err("");
+    let err = low.set_extension(OurError::ioctl(
+        DeviceInfo::new("dm-0", 253, 0),
+        IoctlRequest::from(IoctlCommand::TableLoad),
+        EBUSY,
+    ));
+    assert_eq!(err.raw_os_error(), Some(EBUSY));
+    assert_eq!(err.constituents().count(), 2);
+    assert!(err.constituents().any(|c| c.is::<OurError>()));
+    assert!(err
+        .report()
+        .to_string()
+        .contains("caused by: Device or resource busy (os error 16)"));
+}
+
+#[test]
+fn set_subsequent_keeps_existing_previous_errors() {
+    let mut later = OurError::new(OurErrorKind::ContextInitError);
+    later.add_previous(Box::new(std::io::Error::other("first")));
+    let err = c().expect_err("").set_subsequent(later);
+    assert_eq!(
+        err.previous_errors()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>(),
+        vec!["first", "DM context not initialized"]
+    );
+}
+
+#[test]
+fn set_constituent_replaces_constituents() {
+    let mut err = OurError::from(std::io::Error::other("io"));
+    err.set_constituent(Box::new(std::io::Error::other("replacement")));
+    assert_eq!(
+        err.constituents()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>(),
+        vec!["replacement"]
+    );
+}
+
+#[test]
+fn result_too_large_records_sizes() {
+    let err = d().expect_err("");
+    match err.kind() {
+        OurErrorKind::IoctlResultTooLarge {
+            request,
+            requested,
+            maximum,
+        } => {
+            assert_eq!(request.command(), Some(IoctlCommand::ListDevices));
+            assert_eq!(*requested, 1 << 32);
+            assert_eq!(*maximum, u64::from(u32::MAX));
+        }
+        _ => panic!("expected a result too large error"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "ioctl DM_LIST_DEVICES result of 4294967296 bytes is too large for \
+         maximum buffer size 4294967295 bytes"
+    );
+}
+
+#[test]
+fn metadata_io_keeps_io_error() {
+    let err = g().expect_err("");
+    assert_eq!(err.code().to_string(), "DM-0005");
+    assert_eq!(
+        err.to_string(),
+        "failed to stat metadata for device at /dev/mapper/no-such-device"
+    );
+    assert_eq!(err.io_error_kind(), Some(std::io::ErrorKind::NotFound));
+    assert_eq!(err.raw_os_error(), Some(ENOENT));
+}
+
+#[test]
+fn ioctl_keeps_os_error() {
+    assert_eq!(e().expect_err("").raw_os_error(), Some(EBUSY));
+}
+
+#[test]
+fn io_error_is_none_without_io_constituent() {
+    let err = d().expect_err("");
+    assert!(err.io_error().is_none());
+    assert!(err.io_error_kind().is_none());
+    assert!(err.raw_os_error().is_none());
+}
+
+#[test]
+fn question_mark_converts_io_error() {
+    let err = h().expect_err("");
+    assert_eq!(err.kind(), &OurErrorKind::IoError);
+    assert_eq!(err.code().to_string(), "DM-0006");
+    assert_eq!(err.io_error_kind(), Some(std::io::ErrorKind::NotFound));
+}
+
+#[test]
+fn strings_convert_to_other() {
+    let other = OurErrorKind::Other {
+        description: "no device".into(),
+    };
+    assert_eq!(OurError::from("no device").kind(), &other);
+    assert_eq!(OurError::from(String::from("no device")).kind(), &other);
+    assert_eq!(OurError::from("no device").to_string(), "no device");
+}
+
+#[cfg(feature = "nix")]
+#[test]
+fn nix_error_converts_to_io_error() {
+    let err = OurError::from(nix::Error::EBUSY);
+    assert_eq!(err.kind(), &OurErrorKind::IoError);
+    assert!(err.find::<nix::Error>().is_some());
+}
+
+#[test]
+fn converts_into_io_error_of_matching_kind() {
+    let io = std::io::Error::from(g().expect_err(""));
+    assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(
+        io.get_ref()
+            .expect("")
+            .downcast_ref::<OurError>()
+            .expect("")
+            .code()
+            .to_string(),
+        "DM-0005"
+    );
+    assert_eq!(
+        std::io::Error::from(OurError::new(OurErrorKind::InvalidArgument {
+            description: "32".into()
+        }))
+        .kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        std::io::Error::from(e().expect_err("")).kind(),
+        std::io::ErrorKind::ResourceBusy
+    );
+    assert_eq!(
+        std::io::Error::from(d().expect_err("")).kind(),
+        std::io::ErrorKind::Other
+    );
+}
+
+#[test]
+fn error_is_returned_from_thread() {
+    let joined = std::thread::spawn(f).join().expect("").expect_err("");
+    assert_eq!(joined.code().to_string(), "DM-0002");
+    assert_eq!(joined.tree().count(), f().expect_err("").tree().count());
+}
+
+#[test]
+fn take_constituent_as_takes_only_that_type() {
+    let mut ioctl = OurError::ioctl(
+        DeviceInfo::new("dm-0", 253, 0),
+        IoctlRequest::from(IoctlCommand::DevRemove),
+        EBUSY,
+    );
+    assert!(ioctl.take_constituent_as::<OurError>().is_none());
+    assert_eq!(ioctl.constituents().count(), 1);
+    let os = ioctl.take_constituent_as::<std::io::Error>().expect("");
+    assert_eq!(os.raw_os_error(), Some(EBUSY));
+    assert!(ioctl.constituent().is_none());
+    assert!(ioctl.take_constituent().is_none());
+}
+
+#[test]
+fn take_previous_takes_the_first() {
+    let mut err = f().expect_err("");
+    let first = err.take_previous_as::<OurError>().expect("");
+    assert_eq!(first.to_string(), dev_remove_failed("dm-0 (253:0)"));
+    assert!(err.take_previous_as::<std::io::Error>().is_none());
+    let second = err.take_previous().expect("");
+    assert_eq!(second.to_string(), dev_remove_failed("dm-1 (253:1)"));
+    assert!(err.previous().is_none());
+    assert_eq!(err.constituents().count(), 1);
+}
+
+// The message of a failure to remove the device described by info
+fn dev_remove_failed(info: &str) -> String {
+    format!(
+        "ioctl DM_DEV_REMOVE failed: Device or resource busy (errno 16), \
+         device info: {}, event 0, flags 0x0",
+        info
+    )
+}
+
+#[test]
+fn into_parts_keeps_kind_and_suberrors() {
+    let many = f().expect_err("");
+    let (kind, suberrors, _) = f().expect_err("").into_parts();
+    assert_eq!(
+        kind,
+        OurErrorKind::InvalidArgument {
+            description: "teardown".into()
+        }
+    );
+    assert_eq!(
+        suberrors
+            .iter()
+            .map(|s| matches!(s, Suberror::Constituent(_)))
+            .collect::<Vec<_>>(),
+        vec![true, false, false]
+    );
+
+    let mut other = OurError::from("teardown failed");
+    for suberror in suberrors {
+        other.add_suberror(suberror);
+    }
+    assert_eq!(other.tree().count(), many.tree().count());
+    assert_eq!(other.previous_errors().count(), 2);
+    assert_eq!(other.constituents().count(), 1);
+}
+
+#[test]
+fn result_ext_is_lazy_on_ok() {
+    let ok: Result<u32, std::io::Error> = Ok(7);
+    assert_eq!(
+        ok.extend_with(|| panic!("kind obtained for Ok")).expect(""),
+        7
+    );
+    let ok: Result<u32, std::io::Error> = Ok(7);
+    assert_eq!(
+        ok.followed_by(|| panic!("kind obtained for Ok")).expect(""),
+        7
+    );
+}
+
+#[test]
+fn extend_with_makes_foreign_error_a_constituent() {
+    let err: Result<(), std::io::Error> = Err(std::io::Error::other("io"));
+    let err = err
+        .extend_with(|| OurErrorKind::ContextInitError)
+        .expect_err("");
+    assert_eq!(err.kind(), &OurErrorKind::ContextInitError);
+    assert!(err.previous().is_none());
+    assert_eq!(
+        err.chain().map(|l| l.relation).collect::<Vec<_>>(),
+        vec![None, Some(Relation::Constituent)]
+    );
+    assert_eq!(err.constituent().expect("").to_string(), "io");
+}
+
+#[test]
+fn followed_by_makes_foreign_error_previous() {
+    let err: Result<(), std::io::Error> = Err(std::io::Error::other("io"));
+    let err = err
+        .followed_by(|| OurErrorKind::ContextInitError)
+        .expect_err("");
+    assert_eq!(err.kind(), &OurErrorKind::ContextInitError);
+    assert!(err.constituent().is_none());
+    assert_eq!(
+        err.chain().map(|l| l.relation).collect::<Vec<_>>(),
+        vec![None, Some(Relation::Previous)]
+    );
+    assert_eq!(err.previous().expect("").to_string(), "io");
+}
@@ -0,0 +1,111 @@
+use backtrace::Backtrace;
+use rust_error_management::{
+    set_backtrace_capture, BacktraceCapture, FrameFilter, OurError, OurErrorKind,
+};
+
+#[inline(never)]
+fn inner() -> OurError {
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+    OurError::new(OurErrorKind::ContextInitError)
+}
+
+#[inline(never)]
+fn outer() -> OurError {
+    inner()
+}
+
+// The names of the symbols of each frame, joined
+fn names(backtrace: &Backtrace) -> Vec<String> {
+    backtrace
+        .frames()
+        .iter()
+        .map(|frame| {
+            frame
+                .symbols()
+                .iter()
+                .filter_map(|s| s.name().map(|n| format!("{:#}", n)))
+                .collect::<Vec<_>>()
+                .join(" / ")
+        })
+        .collect()
+}
+
+fn filtered(err: &OurError, filter: FrameFilter) -> Vec<String> {
+    names(&err.filtered_backtrace(&filter).expect(""))
+}
+
+#[test]
+fn default_filter_keeps_only_callers_frames() {
+    let names = filtered(&outer(), FrameFilter::new());
+    assert_eq!(names[..2], ["filter::inner", "filter::outer"]);
+    assert!(
+        names.iter().all(|n| n.starts_with("filter::")),
+        "{:?}",
+        names
+    );
+}
+
+#[test]
+fn default_filter_trims_thread_runtime() {
+    let err = std::thread::spawn(outer).join().expect("");
+    assert_eq!(
+        filtered(&err, FrameFilter::new()),
+        ["filter::inner", "filter::outer"]
+    );
+}
+
+#[test]
+fn capture_frames_are_kept_if_requested() {
+    let err = outer();
+    let names = filtered(&err, FrameFilter::new().skip_capture_frames(false));
+    // Which of the frames of this crate and of backtrace remain depends on
+    // inlining, so only check that they precede the caller's frames.
+    let inner = names.iter().position(|n| n == "filter::inner").expect("");
+    assert!(inner > 0, "{:?}", names);
+    assert!(
+        names[..inner]
+            .iter()
+            .all(|n| n.starts_with("rust_error_management::") || n.starts_with("backtrace::")),
+        "{:?}",
+        names
+    );
+    assert_eq!(names[inner..], filtered(&err, FrameFilter::new())[..]);
+}
+
+#[test]
+fn runtime_frames_are_kept_if_requested() {
+    let err = outer();
+    let names = filtered(&err, FrameFilter::new().skip_runtime_frames(false));
+    assert!(names
+        .iter()
+        .any(|n| n.contains("__rust_begin_short_backtrace")));
+    let kept = filtered(&err, FrameFilter::new());
+    assert_eq!(names[..kept.len()], kept[..]);
+    assert!(names.len() > kept.len());
+}
+
+#[test]
+fn only_crates_keeps_frames_of_those_crates() {
+    let err = outer();
+    let unfiltered = FrameFilter::new()
+        .skip_capture_frames(false)
+        .skip_runtime_frames(false);
+    let all = filtered(&err, unfiltered.clone());
+
+    // Exactly the frames of the listed crate are kept, in order.
+    for krate in &["filter", "rust_error_management"] {
+        let names = filtered(&err, unfiltered.clone().only_crates(vec![*krate]));
+        let prefix = format!("{}::", krate);
+        assert!(!names.is_empty());
+        assert_eq!(
+            names,
+            all.iter()
+                .filter(|n| n.starts_with(&prefix))
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    let names = filtered(&err, unfiltered.only_crates(vec!["filter"]));
+    assert_eq!(names[..2], ["filter::inner", "filter::outer"]);
+}
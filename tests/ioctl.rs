@@ -0,0 +1,71 @@
+use rust_error_management::{IoctlCommand, IoctlRequest, OurErrorKind};
+
+mod common;
+
+use common::{e, EBUSY};
+
+const COMMANDS: &[IoctlCommand] = &[
+    IoctlCommand::Version,
+    IoctlCommand::RemoveAll,
+    IoctlCommand::ListDevices,
+    IoctlCommand::DevCreate,
+    IoctlCommand::DevRemove,
+    IoctlCommand::DevRename,
+    IoctlCommand::DevSuspend,
+    IoctlCommand::DevStatus,
+    IoctlCommand::DevWait,
+    IoctlCommand::TableLoad,
+    IoctlCommand::TableClear,
+    IoctlCommand::TableDeps,
+    IoctlCommand::TableStatus,
+    IoctlCommand::ListVersions,
+    IoctlCommand::TargetMsg,
+    IoctlCommand::DevSetGeometry,
+    IoctlCommand::DevArmPoll,
+    IoctlCommand::GetTargetVersion,
+];
+
+#[test]
+fn request_encodes_command() {
+    for command in COMMANDS {
+        let request = IoctlRequest::from(*command);
+        assert_eq!(request.command(), Some(*command));
+        assert_eq!(request.to_string(), command.name());
+    }
+}
+
+#[test]
+fn request_matches_linux() {
+    // _IOWR(DM_IOCTL, DM_DEV_REMOVE_CMD, struct dm_ioctl)
+    assert_eq!(IoctlRequest::from(IoctlCommand::DevRemove).0, 0xc138_fd04);
+    assert_eq!(IoctlRequest::from(IoctlCommand::TableLoad).0, 0xc138_fd09);
+}
+
+#[test]
+fn unknown_request_is_displayed_raw() {
+    let request = IoctlRequest(0x5401);
+    assert_eq!(request.command(), None);
+    assert_eq!(request.to_string(), "0x5401");
+}
+
+#[test]
+fn ioctl_error_records_device_request_and_errno() {
+    let err = e().expect_err("");
+    match err.kind() {
+        OurErrorKind::IoctlError {
+            device_info,
+            request,
+            errno,
+        } => {
+            assert_eq!(device_info.minor, 0);
+            assert_eq!(request.command(), Some(IoctlCommand::TableLoad));
+            assert_eq!(*errno, EBUSY);
+        }
+        _ => panic!("expected an ioctl error"),
+    }
+    assert_eq!(
+        err.kind().to_string(),
+        "ioctl DM_TABLE_LOAD failed: Device or resource busy (errno 16), \
+         device info: dm-0 (253:0), event 0, flags 0x0"
+    );
+}
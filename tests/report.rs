@@ -0,0 +1,156 @@
+use std::sync::{Mutex, MutexGuard};
+
+use rust_error_management::{
+    set_backtrace_capture, BacktraceCapture, Backtraces, FrameFilter, OurError, OurErrorKind,
+};
+
+mod common;
+
+use common::{d, f};
+
+// The policy is global, so the tests which change it must not run
+// concurrently.
+static POLICY: Mutex<()> = Mutex::new(());
+
+// Enable capture for as long as the returned guard is held.
+fn capturing() -> MutexGuard<'static, ()> {
+    let guard = POLICY.lock().unwrap_or_else(|e| e.into_inner());
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+    guard
+}
+
+#[inline(never)]
+fn low() -> OurError {
+    OurError::new(OurErrorKind::ContextInitError)
+}
+
+// An error extending low(), created in the same function, so that their
+// backtraces share all the frames below this one
+#[inline(never)]
+fn high() -> OurError {
+    low().set_extension(OurError::new(OurErrorKind::IoError))
+}
+
+// A caller of high(), so that the errors it creates share at least this
+// frame whatever else is inlined
+#[inline(never)]
+fn caller() -> OurError {
+    high()
+}
+
+// The names of the functions of the frames of the backtrace rendered in a
+// report, in order
+fn functions(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| {
+            line.split(": ")
+                .next()
+                .is_some_and(|n| n.parse::<usize>().is_ok())
+        })
+        .map(|line| line.split(": ").nth(1).expect("").to_string())
+        .collect()
+}
+
+// The backtrace of the constituent of err, filtered as in a report
+fn below(err: &OurError) -> backtrace::Backtrace {
+    err.constituent()
+        .expect("")
+        .downcast_ref::<OurError>()
+        .expect("")
+        .filtered_backtrace(&FrameFilter::new())
+        .expect("")
+}
+
+// The number of frames a report says were elided, if any
+fn elided(text: &str) -> Option<usize> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix("... "))
+        .find_map(|line| line.split(' ').next()?.parse().ok())
+}
+
+#[test]
+fn report_labels_relations() {
+    let err = f().expect_err("");
+    assert_eq!(
+        err.report().to_string(),
+        "[DM-0002] invalid argument: teardown\n  \
+         caused by: [DM-0001] DM context not initialized\n    \
+         caused by: oh no!\n  \
+         preceded by: [DM-0003] ioctl DM_DEV_REMOVE failed: Device or resource busy \
+         (errno 16), device info: dm-0 (253:0), event 0, flags 0x0\n    \
+         caused by: Device or resource busy (os error 16)\n  \
+         preceded by: [DM-0003] ioctl DM_DEV_REMOVE failed: Device or resource busy \
+         (errno 16), device info: dm-1 (253:1), event 0, flags 0x0\n    \
+         caused by: Device or resource busy (os error 16)\n"
+    );
+}
+
+#[test]
+fn report_indents_by_depth() {
+    let err = d().expect_err("");
+    assert_eq!(
+        err.report().to_string(),
+        "[DM-0004] ioctl DM_LIST_DEVICES result of 4294967296 bytes is too large for \
+         maximum buffer size 4294967295 bytes\n  \
+         preceded by: [DM-0002] invalid argument: 32\n    \
+         caused by: [DM-0001] DM context not initialized\n      \
+         caused by: oh no!\n"
+    );
+}
+
+#[test]
+fn report_elides_frames_in_common() {
+    let _guard = capturing();
+    let err = caller();
+    let above = err.filtered_backtrace(&FrameFilter::new()).expect("");
+    let text = err.report().backtraces(Backtraces::All).to_string();
+
+    let (head, rest) = text.split_once("  caused by: ").expect("");
+    assert_eq!(functions(head).len(), above.frames().len());
+
+    // Which frames are shared depends on inlining, but those rendered and
+    // those elided together make up the whole backtrace.
+    let common = elided(rest).expect("");
+    assert!(common > 0);
+    assert!(rest.contains(&format!(
+        "... {} {} in common with the error above\n",
+        common,
+        if common == 1 { "frame" } else { "frames" }
+    )));
+    assert_eq!(functions(rest).len() + common, below(&err).frames().len());
+}
+
+#[test]
+fn report_counts_a_single_frame_in_common() {
+    let _guard = capturing();
+    let err = std::thread::spawn(caller).join().expect("");
+    let text = err.report().backtraces(Backtraces::All).to_string();
+    let (_, rest) = text.split_once("  caused by: ").expect("");
+    assert!(rest.contains("... 1 frame in common with the error above\n"));
+    assert_eq!(functions(rest).len() + 1, below(&err).frames().len());
+}
+
+#[test]
+fn report_keeps_frames_in_common_if_requested() {
+    let _guard = capturing();
+    let err = high();
+    let text = err
+        .report()
+        .backtraces(Backtraces::All)
+        .elide_common_frames(false)
+        .to_string();
+    let (_, rest) = text.split_once("  caused by: ").expect("");
+    assert_eq!(functions(rest).len(), below(&err).frames().len());
+    assert!(!text.contains("in common"));
+}
+
+#[test]
+fn report_renders_only_the_head_backtrace() {
+    let _guard = capturing();
+    let text = high().report().backtraces(Backtraces::Head).to_string();
+    let (head, rest) = text.split_once("  caused by: ").expect("");
+    assert!(head.contains("  backtrace:\n"));
+    assert!(!rest.contains("backtrace:"));
+    assert!(functions(rest).is_empty());
+}
@@ -0,0 +1,221 @@
+#![cfg(feature = "serde")]
+
+use rust_error_management::{
+    set_backtrace_capture, BacktraceCapture, DeviceInfo, IoctlCommand, IoctlRequest, OurError,
+    OurErrorKind, Relation, RemoteError, Report, Serialized,
+};
+
+mod common;
+
+use common::{d, e, EBUSY};
+
+#[test]
+fn serializes_kind_and_code() {
+    let value = serde_json::to_value(d().expect_err("")).expect("");
+    assert_eq!(value["type_name"], "OurError");
+    assert_eq!(value["kind"]["tag"], "IoctlResultTooLarge");
+    assert_eq!(value["code"], "DM-0004");
+    assert_eq!(value["code_number"], 4);
+    assert_eq!(
+        value["kind"]["request"],
+        IoctlRequest::from(IoctlCommand::ListDevices).0
+    );
+    assert_eq!(value["kind"]["requested"], 1u64 << 32);
+    assert_eq!(value["kind"]["maximum"], u32::MAX);
+    assert!(value.get("relation").is_none());
+    assert!(value.get("backtrace").is_none());
+}
+
+#[test]
+fn serializes_sources_with_relations() {
+    let value = serde_json::to_value(d().expect_err("")).expect("");
+    let previous = &value["sources"][0];
+    assert_eq!(previous["relation"], "previous");
+    assert_eq!(previous["kind"]["tag"], "InvalidArgument");
+    assert_eq!(previous["kind"]["description"], "32");
+    assert_eq!(previous["message"], "invalid argument: 32");
+
+    // The foreign io error is identified by its type and message.
+    let foreign = &previous["sources"][0]["sources"][0];
+    assert_eq!(foreign["relation"], "constituent");
+    assert_eq!(foreign["type_name"], "std::io::Error");
+    assert_eq!(foreign["message"], "oh no!");
+    assert!(foreign.get("kind").is_none());
+    assert!(foreign.get("code").is_none());
+}
+
+#[test]
+fn serializes_type_names_of_std_errors() {
+    let err = "x".parse::<i32>().expect_err("");
+    let value = serde_json::to_value(Serialized::new(&err)).expect("");
+    assert_eq!(value["type_name"], "std::num::ParseIntError");
+
+    // An error of an unrecognized type has no type name.
+    let err = std::io::Error::other("oh no!");
+    let value = serde_json::to_value(Serialized::new(err.get_ref().expect(""))).expect("");
+    assert!(value["type_name"].is_null());
+}
+
+#[test]
+fn serializes_backtraces_only_if_requested() {
+    set_backtrace_capture(Some(BacktraceCapture::Enabled));
+    let err = d().expect_err("");
+    let value = serde_json::to_value(Serialized::new(&err).backtraces(true)).expect("");
+    let frames = value["backtrace"].as_array().expect("");
+    assert!(!frames.is_empty());
+    assert!(frames[0]["ip"].as_str().expect("").starts_with("0x"));
+    assert!(value["sources"][0]["backtrace"].is_array());
+    assert!(serde_json::to_value(&err)
+        .expect("")
+        .get("backtrace")
+        .is_none());
+}
+
+#[test]
+fn serializes_device_info() {
+    let mut info = DeviceInfo::new("dm-0", 253, 0);
+    info.uuid = Some("LVM-xyz".into());
+    let request = IoctlRequest::from(IoctlCommand::TableLoad);
+    let ioctl = OurError::ioctl(info, request, EBUSY);
+    let value = serde_json::to_value(&ioctl).expect("");
+    assert_eq!(value["kind"]["request"], request.0);
+    assert_eq!(value["kind"]["errno"], EBUSY);
+    assert!(value["kind"].get("strerror").is_none());
+    assert!(value["message"]
+        .as_str()
+        .expect("")
+        .contains("failed: Device or resource busy (errno 16)"));
+    assert_eq!(value["sources"][0]["type_name"], "std::io::Error");
+    assert_eq!(value["kind"]["device_info"]["name"], "dm-0");
+    assert_eq!(value["kind"]["device_info"]["uuid"], "LVM-xyz");
+    assert_eq!(value["kind"]["device_info"]["major"], 253);
+    assert_eq!(value["kind"]["device_info"]["minor"], 0);
+}
+
+#[test]
+fn remote_error_preserves_relations_and_kinds() {
+    let err = d().expect_err("");
+    let json = serde_json::to_string(&err).expect("");
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    assert_eq!(
+        remote
+            .tree()
+            .map(|l| (l.depth, l.relation))
+            .collect::<Vec<_>>(),
+        err.tree()
+            .map(|l| (l.depth, l.relation))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(remote.kind(), Some(err.kind()));
+    assert_eq!(remote.code(), Some("DM-0004"));
+    assert_eq!(remote.code_number(), Some(4));
+    assert_eq!(remote.type_name(), Some("OurError"));
+    assert_eq!(remote.to_string(), err.to_string());
+    assert_eq!(
+        remote.previous().expect("").kind(),
+        Some(&OurErrorKind::InvalidArgument {
+            description: "32".into()
+        })
+    );
+    assert!(remote.constituent().is_none());
+
+    let last = remote.tree().last().expect("");
+    assert_eq!(last.relation, Some(Relation::Constituent));
+    let last = last.error.downcast_ref::<RemoteError>().expect("");
+    assert_eq!(last.type_name(), Some("std::io::Error"));
+    assert_eq!(last.kind(), None);
+    assert_eq!(last.code(), None);
+    assert_eq!(last.to_string(), "oh no!");
+}
+
+#[test]
+fn remote_error_reserializes_to_the_original() {
+    let json = serde_json::to_string(&d().expect_err("")).expect("");
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    assert_eq!(
+        serde_json::to_string(&Serialized::new(&remote)).expect(""),
+        json
+    );
+}
+
+#[test]
+fn remote_error_reports_like_the_original() {
+    let err = d().expect_err("");
+    let remote: RemoteError =
+        serde_json::from_str(&serde_json::to_string(&err).expect("")).expect("");
+    assert_eq!(Report::new(&remote).to_string(), err.report().to_string());
+}
+
+#[test]
+fn remote_error_keeps_unrecognized_kind_opaque() {
+    let remote: RemoteError = serde_json::from_str(
+        r#"{"message": "new", "kind": {"tag": "FromTheFuture"}, "sources": []}"#,
+    )
+    .expect("");
+    assert_eq!(remote.kind(), None);
+    assert_eq!(remote.to_string(), "new");
+
+    // The code of an unrecognized kind is preserved.
+    let remote: RemoteError = serde_json::from_str(
+        r#"{"message": "new", "code": "DM-0099", "code_number": 99, "sources": []}"#,
+    )
+    .expect("");
+    assert_eq!(remote.code(), Some("DM-0099"));
+    assert_eq!(remote.code_number(), Some(99));
+}
+
+#[test]
+fn find_kind_searches_remote_errors() {
+    let json = serde_json::to_string(&e().expect_err("")).expect("");
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    let mut err = OurError::new(OurErrorKind::ContextInitError);
+    err.add_constituent(Box::new(remote));
+    match err.find_kind(|k| matches!(k, OurErrorKind::IoctlError { .. })) {
+        Some(OurErrorKind::IoctlError { errno, .. }) => assert_eq!(*errno, EBUSY),
+        _ => panic!("expected an ioctl error"),
+    }
+}
+
+#[cfg(feature = "nix")]
+#[test]
+fn serializes_nix_error_type_name() {
+    let value = serde_json::to_value(OurError::from(nix::Error::EBUSY)).expect("");
+    assert_eq!(value["sources"][0]["type_name"], "nix::Error");
+    assert_eq!(value["sources"][0]["relation"], "constituent");
+}
+
+#[test]
+fn remote_error_can_be_searched() {
+    let json = serde_json::to_string(&e().expect_err("")).expect("");
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    match remote.find_kind(|k| matches!(k, OurErrorKind::IoctlError { .. })) {
+        Some(OurErrorKind::IoctlError { errno, .. }) => assert_eq!(*errno, EBUSY),
+        _ => panic!("expected an ioctl error"),
+    }
+    assert!(remote
+        .find_kind(|k| k == &OurErrorKind::ContextInitError)
+        .is_some());
+    assert!(std::ptr::eq(
+        remote.find::<RemoteError>().expect(""),
+        &remote
+    ));
+    // The io errors were deserialized as remote errors, too.
+    assert!(remote.find::<std::io::Error>().is_none());
+    assert!(remote
+        .find_along::<std::io::Error>(Relation::Constituent)
+        .is_none());
+}
+
+#[test]
+fn remote_error_has_root_cause_and_earliest() {
+    let json = serde_json::to_string(&d().expect_err("")).expect("");
+    let remote: RemoteError = serde_json::from_str(&json).expect("");
+    // The newest error only followed the others, so it is its own root cause.
+    assert!(std::ptr::eq(
+        remote.root_cause().downcast_ref::<RemoteError>().expect(""),
+        &remote
+    ));
+    let earliest = remote.earliest().downcast_ref::<RemoteError>().expect("");
+    assert_eq!(earliest.to_string(), "invalid argument: 32");
+    assert_eq!(earliest.root_cause().to_string(), "oh no!");
+}